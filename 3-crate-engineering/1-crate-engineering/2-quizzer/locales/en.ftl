@@ -0,0 +1,242 @@
+# The messages of quizzer in English. Every message here must also be in the
+# other catalogues, with the same arguments.
+
+yes = y
+no = n
+no-questions =
+    There are no quiz questions.
+    First add some with ./quizzer add
+problems-warning = Warning: { $file } has { $count } problems, see ./quizzer check
+
+## Adding questions
+
+add-enter-question = Enter a new question:
+add-empty-question = The question cannot be empty.
+add-similar = Warning: this looks like question { $number }: { $text }
+add-enter-kind = Enter the kind of question: multiple-choice, multi-select, free-text or numeric (leave blank for multiple-choice):
+add-unknown-kind = Unknown kind of question '{ $kind }'.
+add-enter-correct = Enter the correct answer:
+add-empty-correct = The correct answer cannot be empty.
+add-enter-corrects = Enter a correct answer (leave blank to finish):
+add-corrects-required = At least one correct answer is required.
+add-enter-incorrects = Enter an incorrect answer (leave blank to finish):
+add-incorrects-required = At least one incorrect answer is required.
+add-is-correct = '{ $answer }' is a correct answer.
+add-repeated = '{ $answer }' was already given.
+add-partial = Give partial credit for picking some of the correct answers? (y/n)
+add-enter-accepted = Enter an accepted answer (leave blank to finish):
+add-enter-pattern = Enter a regular expression matching other accepted answers (leave blank for none):
+add-accepted-required = At least one accepted answer or a pattern is required.
+add-invalid-pattern = Invalid regular expression: { $error }
+add-enter-number = Enter the correct number:
+add-not-a-number = '{ $answer }' is not a number.
+add-enter-tolerance = Enter how far off an answer can be (leave blank for exactly):
+add-bad-tolerance = '{ $answer }' is not a number of zero or more.
+add-enter-tags = Enter tags, separated by commas (leave blank for none):
+add-enter-difficulty = Enter the difficulty: easy, medium or hard (leave blank for none):
+add-unknown-difficulty = unknown difficulty '{ $difficulty }', expected easy, medium or hard.
+add-enter-explanation = Enter an explanation of the answer (leave blank for none):
+add-enter-reference = Enter a reference to read more, such as a URL or chapter (leave blank for none):
+add-duplicate = The quiz already has this question as question { $number }, so it was not added.
+add-unused-option = { $option } cannot be used with { $kind } questions.
+add-no-incorrects = { $kind } questions have no incorrect answers.
+add-missing-correct = Missing the correct answer.
+add-correct-not-a-number = The correct answer '{ $answer }' is not a number.
+add-invalid = { $problem }.
+add-already = The quiz already has this question as question { $number }.
+add-added = Question added!
+add-continue = Continue adding questions? (y/n)
+
+## Taking a quiz
+
+quiz-no-match = No questions match the selected tags and difficulty.
+quiz-pick-every = (Pick every correct answer, separated by commas)
+quiz-progress = Question { $number } of { $total }, score { $score }
+quiz-hint-pick = ↑/↓ move, space picks, enter answers
+quiz-hint-choose = ↑/↓ move, enter answers
+quiz-correct = Correct!
+quiz-partly-correct = Partly correct, { $points } of a point.
+quiz-wrong = Wrong answer.
+quiz-time-up = Time's up!
+quiz-explanation = Explanation: { $text }
+quiz-reference = Reference: { $text }
+quiz-input-ended = Input ended after { $answered } of { $total } questions. The { $remaining } remaining questions count as wrong.
+quiz-out-of-time = Out of time! The { $remaining } remaining questions count as wrong.
+quiz-times = Time per question:
+quiz-timed-out = { $seconds }s (timed out)
+quiz-not-answered = not answered
+quiz-score = You scored { $score } out of { $total } points.
+quiz-unscored = The quiz is sealed with a key, so your answers are not scored but recorded in { $file }.
+quiz-recorded = Your answers were recorded in { $file }, to be graded with ./quizzer grade --key
+quiz-save-failed = Failed to save the run to '{ $file }': { $error }
+quiz-nothing-to-review = Nothing to review, every answer was right.
+quiz-review = Review of the missed questions:
+quiz-your-answer = Your answer: { $answer }
+quiz-no-answer-in-time = none, the time ran out
+quiz-no-answer = none, it was not answered
+quiz-correct-answer = Correct answer: { $answer }
+
+## Playing with several players
+
+players-none = Name at least one player.
+players-same-name = Every player needs a different name.
+players-turn = { $name }'s turn:
+players-question = Question { $number } of { $total }, the first right answer wins the point.
+players-nobody = Nobody got it right. The answer was: { $answer }
+players-input-ended = Input ended after { $answered } of { $total } questions.
+players-leaderboard = Leaderboard:
+players-points = { $points } points
+players-points-of = { $points } out of { $total } points
+players-wins = { $name } wins!
+players-tie = It's a tie between { $names } and { $last }!
+
+## Studying
+
+study-nothing = Nothing to study right now.
+study-nothing-until = Nothing to study right now. The next question is due in { $wait }.
+study-days = { $count ->
+    [one] 1 day
+   *[other] { $count } days
+}
+study-hours = { $count ->
+    [one] 1 hour
+   *[other] { $count } hours
+}
+study-minutes = { $count ->
+    [one] 1 minute
+   *[other] { $count } minutes
+}
+study-done = You studied { $studied } questions and got { $score } right.
+
+## Editing questions
+
+edit-start = Editing question { $number }. Leave an answer blank to keep the current value.
+edit-text = Question [{ $current }]:
+edit-correct = Correct answer [{ $current }]:
+edit-incorrect = Incorrect answer { $number } [{ $current }] (enter '-' to remove it):
+edit-add-incorrect = Add an incorrect answer (leave blank to finish):
+edit-correct-n = Correct answer { $number } [{ $current }] (enter '-' to remove it):
+edit-add-correct = Add a correct answer (leave blank to finish):
+edit-accepted = Accepted answer { $number } [{ $current }] (enter '-' to remove it):
+edit-add-accepted = Add an accepted answer (leave blank to finish):
+edit-partial = Give partial credit for picking some of the correct answers? [{ $current }] (y/n)
+edit-pattern = Regular expression matching other accepted answers [{ $current }] (enter '-' to clear it):
+edit-number = Correct number [{ $current }]:
+edit-tolerance = How far off an answer can be [{ $current }]:
+edit-sealed = The answers of sealed questions cannot be edited and are kept.
+edit-tags = Tags [{ $current }] (enter '-' to clear them):
+edit-difficulty = Difficulty [{ $current }] (easy, medium or hard, enter '-' to clear it):
+edit-explanation = Explanation [{ $current }] (enter '-' to clear it):
+edit-reference = Reference [{ $current }] (enter '-' to clear it):
+edit-none = none
+edit-updated = Question { $number } updated!
+edit-removed = Removed question { $number }: { $text }
+edit-invalid = Question { $number } was not updated: { $problem }.
+
+## Checking questions
+
+check-problem = Question { $number }: { $problem }
+check-fixed = Fixed { $problem }
+check-fixable = { $problem } (fixable)
+check-clean = No problems found in { $file }.
+problem-empty-question = the question is empty
+problem-empty-correct = the correct answer is empty
+problem-empty-answer = { $answers ->
+    [correct] a correct answer is empty
+    [incorrect] an incorrect answer is empty
+   *[accepted] an accepted answer is empty
+}
+problem-corrects-required = at least one correct answer is required
+problem-incorrects-required = at least one incorrect answer is required
+problem-accepted-required = at least one accepted answer or a pattern is required
+problem-invalid-pattern = the pattern is not a valid regular expression
+problem-not-a-number = the answer is not a number
+problem-negative-tolerance = the tolerance cannot be negative
+problem-choices-required = at least two choices are required
+problem-also-correct = the incorrect answer '{ $answer }' is also a correct answer
+problem-repeated = { $answers ->
+    [correct] the correct answer '{ $answer }' is repeated
+    [incorrect] the incorrect answer '{ $answer }' is repeated
+   *[accepted] the accepted answer '{ $answer }' is repeated
+}
+problem-same = is the same as question { $number }
+problem-similar = looks like a duplicate of question { $number }
+
+## Importing questions
+
+import-similar = Warning: '{ $question }' looks like question { $number }: { $text }
+import-done = Imported { $count } questions.
+import-line = line { $line }: { $problem }
+import-parse-error = { $error }
+import-already = '{ $question }' is already question { $number } of the quiz
+import-same-line = the same question as on line { $line }
+import-same-question = the same question as question { $number }
+import-unknown-difficulty = unknown difficulty '{ $difficulty }', expected easy, medium or hard
+import-field-first = { $field } given before any question
+import-answer-first = answer given before any question
+import-unexpected-line = expected a '#' question heading or a '- [ ]' answer
+import-none-checked = no answer is checked
+import-several-checked = more than one answer is checked
+
+## Listing quizzes
+
+list-answer = answer: { $answer }
+list-no-quizzes = No quizzes found in '{ $dir }'.
+list-quiz = { $file } ({ $count } questions)
+list-invalid-quiz = { $file } (not a valid quiz)
+
+## Grading answer sheets
+
+grade-extra-answers = { $file } has { $count } answers but the quiz only has { $total } questions, ignoring the rest.
+grade-student = Student
+grade-score = Score
+grade-answers = Answers
+grade-question = Question
+grade-correct = Correct
+grade-legend = + correct, ~ partly correct, - wrong, . unanswered
+
+## Statistics
+
+stats-not-run =
+    { $file } has not been run yet.
+    Run it with ./quizzer quiz
+stats-runs = { $file } was run { $count } times.
+stats-scores = Scores:
+stats-last-scores = Last { $count } scores:
+stats-score = { $score } out of { $total } points
+stats-average = Average score: { $average }, from { $first } in the first run to { $last } in the last.
+stats-average-time = Average time per answer: { $seconds }s
+stats-answered-right = Answered right:
+stats-question = { $question }: { $right } of { $answers } ({ $rate }), { $seconds }s on average
+stats-most-missed = Most missed:
+stats-missed = { $number }. { $question } (missed { $missed } of { $answers } times)
+
+## Serving a quiz
+
+serve-started = Serving { $file } on http://{ $address }, press Ctrl-C to stop.
+serve-unnamed = Session { $id }
+serve-joined = { $name } started the quiz.
+serve-finished = { $name } finished with { $score } out of { $total } points.
+
+## The command line
+
+try-help = Try ./quizzer --help
+tui-needs-terminal = --tui needs a terminal to read keys from.
+db-failed = Failed to open the database '{ $file }': { $error }
+
+## Errors
+
+error-load = Failed to load { $file }: { $error }
+error-save = Failed to save quiz to '{ $file }': { $error }
+error-study-log = Failed to save study log to '{ $file }': { $error }
+error-results = Failed to write results to '{ $file }': { $error }
+error-serve = Failed to serve the quiz on { $address }: { $error }
+error-no-such-question = There is no question { $number }. The quiz has { $count } questions, see ./quizzer list
+error-unknown-format = Cannot tell the format of '{ $file }', pass it with --format.
+error-import = Failed to import '{ $file }':
+error-export = Failed to export { $file }: { $error }
+error-seal = Failed to seal { $file }: { $error }
+error-sealed-no-key = { $file } is sealed with a key. Pass it with --key, or record the answers with --results to grade them later.
+error-sealed-wrong-key = { $file } is sealed with a different key.
+error-problems = Found { $count } problems in { $file }, { $fixable } of which --fix can fix.
+error-io = Failed to read input or write output: { $error }
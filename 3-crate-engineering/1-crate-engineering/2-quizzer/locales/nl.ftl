@@ -0,0 +1,242 @@
+# De berichten van quizzer in het Nederlands. Elk bericht moet ook in de
+# andere catalogi staan, met dezelfde argumenten.
+
+yes = j
+no = n
+no-questions =
+    De quiz heeft nog geen vragen.
+    Voeg ze eerst toe met ./quizzer add
+problems-warning = Let op: { $file } heeft { $count } problemen, zie ./quizzer check
+
+## Vragen toevoegen
+
+add-enter-question = Typ een nieuwe vraag:
+add-empty-question = De vraag mag niet leeg zijn.
+add-similar = Let op: dit lijkt op vraag { $number }: { $text }
+add-enter-kind = Kies het soort vraag: multiple-choice, multi-select, free-text of numeric (leeg laten voor multiple-choice):
+add-unknown-kind = Onbekend soort vraag '{ $kind }'.
+add-enter-correct = Typ het goede antwoord:
+add-empty-correct = Het goede antwoord mag niet leeg zijn.
+add-enter-corrects = Typ een goed antwoord (leeg laten om te stoppen):
+add-corrects-required = Er is minstens één goed antwoord nodig.
+add-enter-incorrects = Typ een fout antwoord (leeg laten om te stoppen):
+add-incorrects-required = Er is minstens één fout antwoord nodig.
+add-is-correct = '{ $answer }' is een goed antwoord.
+add-repeated = '{ $answer }' is al gegeven.
+add-partial = Punten geven voor een deel van de goede antwoorden? (j/n)
+add-enter-accepted = Typ een goedgekeurd antwoord (leeg laten om te stoppen):
+add-enter-pattern = Typ een reguliere expressie voor andere goede antwoorden (leeg laten voor geen):
+add-accepted-required = Er is minstens één goedgekeurd antwoord of een patroon nodig.
+add-invalid-pattern = Ongeldige reguliere expressie: { $error }
+add-enter-number = Typ het goede getal:
+add-not-a-number = '{ $answer }' is geen getal.
+add-enter-tolerance = Typ hoeveel een antwoord ernaast mag zitten (leeg laten voor precies):
+add-bad-tolerance = '{ $answer }' is geen getal van nul of meer.
+add-enter-tags = Typ de tags, gescheiden door komma's (leeg laten voor geen):
+add-enter-difficulty = Kies de moeilijkheid: easy, medium of hard (leeg laten voor geen):
+add-unknown-difficulty = onbekende moeilijkheid '{ $difficulty }', kies easy, medium of hard.
+add-enter-explanation = Typ een uitleg bij het antwoord (leeg laten voor geen):
+add-enter-reference = Typ een verwijzing om meer te lezen, zoals een URL of hoofdstuk (leeg laten voor geen):
+add-duplicate = De quiz heeft deze vraag al als vraag { $number }, dus hij is niet toegevoegd.
+add-unused-option = { $option } kan niet gebruikt worden bij vragen van het soort { $kind }.
+add-no-incorrects = Vragen van het soort { $kind } hebben geen foute antwoorden.
+add-missing-correct = Het goede antwoord ontbreekt.
+add-correct-not-a-number = Het goede antwoord '{ $answer }' is geen getal.
+add-invalid = { $problem }.
+add-already = De quiz heeft deze vraag al als vraag { $number }.
+add-added = Vraag toegevoegd!
+add-continue = Nog een vraag toevoegen? (j/n)
+
+## Een quiz maken
+
+quiz-no-match = Geen enkele vraag past bij de gekozen tags en moeilijkheid.
+quiz-pick-every = (Kies alle goede antwoorden, gescheiden door komma's)
+quiz-progress = Vraag { $number } van { $total }, score { $score }
+quiz-hint-pick = ↑/↓ verplaatsen, spatie kiest, enter antwoordt
+quiz-hint-choose = ↑/↓ verplaatsen, enter antwoordt
+quiz-correct = Goed!
+quiz-partly-correct = Gedeeltelijk goed, { $points } van een punt.
+quiz-wrong = Fout antwoord.
+quiz-time-up = De tijd is om!
+quiz-explanation = Uitleg: { $text }
+quiz-reference = Meer lezen: { $text }
+quiz-input-ended = De invoer stopte na { $answered } van de { $total } vragen. De { $remaining } overige vragen tellen als fout.
+quiz-out-of-time = De tijd is op! De { $remaining } overige vragen tellen als fout.
+quiz-times = Tijd per vraag:
+quiz-timed-out = { $seconds }s (tijd om)
+quiz-not-answered = niet beantwoord
+quiz-score = Je hebt { $score } van de { $total } punten gehaald.
+quiz-unscored = De quiz is verzegeld met een sleutel, dus je antwoorden worden niet nagekeken maar bewaard in { $file }.
+quiz-recorded = Je antwoorden zijn bewaard in { $file }, om na te kijken met ./quizzer grade --key
+quiz-save-failed = Kon de ronde niet opslaan in '{ $file }': { $error }
+quiz-nothing-to-review = Niets om na te lopen, alle antwoorden waren goed.
+quiz-review = De gemiste vragen nagelopen:
+quiz-your-answer = Jouw antwoord: { $answer }
+quiz-no-answer-in-time = geen, de tijd was om
+quiz-no-answer = geen, de vraag is niet beantwoord
+quiz-correct-answer = Goede antwoord: { $answer }
+
+## Spelen met meerdere spelers
+
+players-none = Noem minstens één speler.
+players-same-name = Elke speler heeft een andere naam nodig.
+players-turn = { $name } is aan de beurt:
+players-question = Vraag { $number } van { $total }, het eerste goede antwoord wint het punt.
+players-nobody = Niemand had het goed. Het antwoord was: { $answer }
+players-input-ended = De invoer stopte na { $answered } van de { $total } vragen.
+players-leaderboard = Ranglijst:
+players-points = { $points } punten
+players-points-of = { $points } van de { $total } punten
+players-wins = { $name } wint!
+players-tie = Gelijkspel tussen { $names } en { $last }!
+
+## Studeren
+
+study-nothing = Er valt nu niets te studeren.
+study-nothing-until = Er valt nu niets te studeren. De volgende vraag komt over { $wait }.
+study-days = { $count ->
+    [one] 1 dag
+   *[other] { $count } dagen
+}
+study-hours = { $count ->
+    [one] 1 uur
+   *[other] { $count } uur
+}
+study-minutes = { $count ->
+    [one] 1 minuut
+   *[other] { $count } minuten
+}
+study-done = Je hebt { $studied } vragen gestudeerd en er { $score } goed gehad.
+
+## Vragen bewerken
+
+edit-start = Vraag { $number } bewerken. Laat een antwoord leeg om de huidige waarde te houden.
+edit-text = Vraag [{ $current }]:
+edit-correct = Goed antwoord [{ $current }]:
+edit-incorrect = Fout antwoord { $number } [{ $current }] (typ '-' om het te verwijderen):
+edit-add-incorrect = Voeg een fout antwoord toe (leeg laten om te stoppen):
+edit-correct-n = Goed antwoord { $number } [{ $current }] (typ '-' om het te verwijderen):
+edit-add-correct = Voeg een goed antwoord toe (leeg laten om te stoppen):
+edit-accepted = Goedgekeurd antwoord { $number } [{ $current }] (typ '-' om het te verwijderen):
+edit-add-accepted = Voeg een goedgekeurd antwoord toe (leeg laten om te stoppen):
+edit-partial = Punten geven voor een deel van de goede antwoorden? [{ $current }] (j/n)
+edit-pattern = Reguliere expressie voor andere goede antwoorden [{ $current }] (typ '-' om hem te wissen):
+edit-number = Goed getal [{ $current }]:
+edit-tolerance = Hoeveel een antwoord ernaast mag zitten [{ $current }]:
+edit-sealed = De antwoorden van verzegelde vragen kunnen niet bewerkt worden en blijven zoals ze zijn.
+edit-tags = Tags [{ $current }] (typ '-' om ze te wissen):
+edit-difficulty = Moeilijkheid [{ $current }] (easy, medium of hard, typ '-' om hem te wissen):
+edit-explanation = Uitleg [{ $current }] (typ '-' om hem te wissen):
+edit-reference = Verwijzing [{ $current }] (typ '-' om hem te wissen):
+edit-none = geen
+edit-updated = Vraag { $number } bijgewerkt!
+edit-removed = Vraag { $number } verwijderd: { $text }
+edit-invalid = Vraag { $number } is niet bijgewerkt: { $problem }.
+
+## Vragen controleren
+
+check-problem = Vraag { $number }: { $problem }
+check-fixed = Opgelost: { $problem }
+check-fixable = { $problem } (op te lossen)
+check-clean = Geen problemen gevonden in { $file }.
+problem-empty-question = de vraag is leeg
+problem-empty-correct = het goede antwoord is leeg
+problem-empty-answer = { $answers ->
+    [correct] een goed antwoord is leeg
+    [incorrect] een fout antwoord is leeg
+   *[accepted] een goedgekeurd antwoord is leeg
+}
+problem-corrects-required = er is minstens één goed antwoord nodig
+problem-incorrects-required = er is minstens één fout antwoord nodig
+problem-accepted-required = er is minstens één goedgekeurd antwoord of een patroon nodig
+problem-invalid-pattern = het patroon is geen geldige reguliere expressie
+problem-not-a-number = het antwoord is geen getal
+problem-negative-tolerance = de marge mag niet negatief zijn
+problem-choices-required = er zijn minstens twee keuzes nodig
+problem-also-correct = het foute antwoord '{ $answer }' is ook een goed antwoord
+problem-repeated = { $answers ->
+    [correct] het goede antwoord '{ $answer }' komt twee keer voor
+    [incorrect] het foute antwoord '{ $answer }' komt twee keer voor
+   *[accepted] het goedgekeurde antwoord '{ $answer }' komt twee keer voor
+}
+problem-same = is hetzelfde als vraag { $number }
+problem-similar = lijkt een dubbele van vraag { $number }
+
+## Vragen importeren
+
+import-similar = Let op: '{ $question }' lijkt op vraag { $number }: { $text }
+import-done = { $count } vragen geïmporteerd.
+import-line = regel { $line }: { $problem }
+import-parse-error = { $error }
+import-already = '{ $question }' is al vraag { $number } van de quiz
+import-same-line = dezelfde vraag als op regel { $line }
+import-same-question = dezelfde vraag als vraag { $number }
+import-unknown-difficulty = onbekende moeilijkheid '{ $difficulty }', kies easy, medium of hard
+import-field-first = { $field } staat voor de eerste vraag
+import-answer-first = antwoord staat voor de eerste vraag
+import-unexpected-line = verwacht een vraag die begint met '#' of een antwoord dat begint met '- [ ]'
+import-none-checked = er is geen antwoord aangevinkt
+import-several-checked = er is meer dan één antwoord aangevinkt
+
+## Quizzen opsommen
+
+list-answer = antwoord: { $answer }
+list-no-quizzes = Geen quizzen gevonden in '{ $dir }'.
+list-quiz = { $file } ({ $count } vragen)
+list-invalid-quiz = { $file } (geen geldige quiz)
+
+## Antwoordbladen nakijken
+
+grade-extra-answers = { $file } heeft { $count } antwoorden maar de quiz heeft maar { $total } vragen, de rest wordt overgeslagen.
+grade-student = Leerling
+grade-score = Score
+grade-answers = Antwoorden
+grade-question = Vraag
+grade-correct = Goed
+grade-legend = + goed, ~ deels goed, - fout, . niet beantwoord
+
+## Statistieken
+
+stats-not-run =
+    { $file } is nog niet gemaakt.
+    Maak hem met ./quizzer quiz
+stats-runs = { $file } is { $count } keer gemaakt.
+stats-scores = Scores:
+stats-last-scores = Laatste { $count } scores:
+stats-score = { $score } van de { $total } punten
+stats-average = Gemiddelde score: { $average }, van { $first } de eerste keer tot { $last } de laatste keer.
+stats-average-time = Gemiddelde tijd per antwoord: { $seconds }s
+stats-answered-right = Goed beantwoord:
+stats-question = { $question }: { $right } van de { $answers } ({ $rate }), gemiddeld { $seconds }s
+stats-most-missed = Het vaakst fout:
+stats-missed = { $number }. { $question } ({ $missed } van de { $answers } keer fout)
+
+## Een quiz aanbieden
+
+serve-started = { $file } wordt aangeboden op http://{ $address }, druk op Ctrl-C om te stoppen.
+serve-unnamed = Sessie { $id }
+serve-joined = { $name } is aan de quiz begonnen.
+serve-finished = { $name } is klaar met { $score } van de { $total } punten.
+
+## De opdrachtregel
+
+try-help = Probeer ./quizzer --help
+tui-needs-terminal = --tui heeft een terminal nodig om toetsen van te lezen.
+db-failed = Kon de database '{ $file }' niet openen: { $error }
+
+## Fouten
+
+error-load = Kan { $file } niet laden: { $error }
+error-save = Kan de quiz niet opslaan in '{ $file }': { $error }
+error-study-log = Kan het studielogboek niet opslaan in '{ $file }': { $error }
+error-results = Kan de resultaten niet schrijven naar '{ $file }': { $error }
+error-serve = Kan de quiz niet aanbieden op { $address }: { $error }
+error-no-such-question = Er is geen vraag { $number }. De quiz heeft { $count } vragen, zie ./quizzer list
+error-unknown-format = Kan het formaat van '{ $file }' niet bepalen, geef het op met --format.
+error-import = Kan '{ $file }' niet importeren:
+error-export = Kan { $file } niet exporteren: { $error }
+error-seal = Kan { $file } niet verzegelen: { $error }
+error-sealed-no-key = { $file } is verzegeld met een sleutel. Geef die op met --key, of bewaar de antwoorden met --results om ze later na te kijken.
+error-sealed-wrong-key = { $file } is verzegeld met een andere sleutel.
+error-problems = { $count } problemen gevonden in { $file }, waarvan --fix er { $fixable } kan oplossen.
+error-io = Kan de invoer niet lezen of de uitvoer niet schrijven: { $error }
@@ -1,74 +1,477 @@
+use clap::ValueEnum;
+
 use crate::{
+    check::{Duplicate, check_question, find_duplicate, similar_question},
+    error::{QuizError, check_io},
+    i18n::Message,
     interface::Interface,
-    quiz::{Question, load_quiz, save_quiz},
+    kind::{Kind, QuestionKind, compile_pattern},
+    quiz::{Difficulty, Question, is_correct_text, load_quiz, parse_tags, update_quiz},
+    store::QuizStore,
 };
 
-pub fn add(inter: &mut dyn Interface) {
-    let res = load_quiz(inter);
-    let mut quiz = match res {
-        Ok(q) => q,
-        Err(e) => {
-            inter.write_stdout_line(&format!("Failed to load quiz.json: {}", e));
-            return;
+/// Interactively adds questions to the quiz in `quiz_file`.
+///
+/// If the input ends part way through, the questions completed so far are
+/// still saved. Questions added meanwhile by another session are kept.
+pub fn add(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+) -> Result<(), QuizError> {
+    // Load the quiz before asking for any questions, to check that it can be
+    // and to spot duplicate questions.
+    let mut questions = load_quiz(store, quiz_file)
+        .map_err(|source| QuizError::Load {
+            file: quiz_file.to_string(),
+            source,
+        })?
+        .questions;
+    let mut added = Vec::new();
+
+    while let Some(new_question) = read_question(inter, &questions) {
+        if let Some(Duplicate::Same(idx)) = find_duplicate(&questions, &new_question) {
+            let msg = inter
+                .messages()
+                .format("add-duplicate", &[("number", (idx + 1).into())]);
+            inter.write_stdout_line(&msg);
+        } else {
+            questions.push(new_question.clone());
+            added.push(new_question);
+            say(inter, "add-added");
+        }
+
+        say(inter, "add-continue");
+        match inter.read_stdin_line() {
+            Some(ans) if inter.messages().is_yes(&ans) => continue,
+            _ => break,
+        }
+    }
+
+    if !added.is_empty() {
+        update_quiz(store, quiz_file, |quiz| {
+            quiz.questions.extend(added.iter().cloned());
+            Ok(())
+        })
+        .map_err(|source| QuizError::Save {
+            file: quiz_file.to_string(),
+            source,
+        })?;
+    }
+
+    check_io(inter)
+}
+
+/// Asks for the text, kind, answers, tags and difficulty of a new question,
+/// warning if the text looks like one of the `existing` questions.
+/// Returns `None` if the input ends before the question is complete.
+fn read_question(inter: &mut dyn Interface, existing: &[Question]) -> Option<Question> {
+    let text = loop {
+        say(inter, "add-enter-question");
+        let text = inter.read_stdin_line()?;
+        if !text.trim().is_empty() {
+            break text;
         }
+        say(inter, "add-empty-question");
     };
+    if let Some(idx) = similar_question(existing, &text) {
+        warn_similar(inter, idx, &existing[idx]);
+    }
 
+    let kind = match read_kind(inter)? {
+        Kind::MultipleChoice => {
+            let correct = loop {
+                say(inter, "add-enter-correct");
+                let correct = inter.read_stdin_line()?;
+                if !correct.trim().is_empty() {
+                    break correct;
+                }
+                say(inter, "add-empty-correct");
+            };
+            let incorrects = read_incorrects(inter, std::slice::from_ref(&correct))?;
+            QuestionKind::MultipleChoice {
+                correct,
+                incorrects,
+            }
+        }
+        Kind::MultiSelect => {
+            let corrects = read_answers(inter, "add-enter-corrects", "add-corrects-required", &[])?;
+            let incorrects = read_incorrects(inter, &corrects)?;
+            say(inter, "add-partial");
+            let ans = inter.read_stdin_line()?;
+            let partial = inter.messages().is_yes(&ans);
+            QuestionKind::MultiSelect {
+                corrects,
+                incorrects,
+                partial,
+            }
+        }
+        Kind::FreeText => read_free_text(inter)?,
+        Kind::Numeric => read_numeric(inter)?,
+    };
+
+    say(inter, "add-enter-tags");
+    let tags = parse_tags(&inter.read_stdin_line()?);
+
+    let difficulty = read_difficulty(inter)?;
+
+    say(inter, "add-enter-explanation");
+    let explanation = read_note(inter)?;
+    say(inter, "add-enter-reference");
+    let reference = read_note(inter)?;
+
+    Some(Question {
+        text,
+        kind,
+        tags,
+        difficulty,
+        explanation,
+        reference,
+    })
+}
+
+/// Reads an optional explanation or reference, which is `None` if left blank.
+/// Returns `None` if there is no more input.
+fn read_note(inter: &mut dyn Interface) -> Option<Option<String>> {
+    let ans = inter.read_stdin_line()?;
+    let ans = ans.trim();
+    Some((!ans.is_empty()).then(|| ans.to_string()))
+}
+
+/// Asks for the kind of question until a valid one is given, defaulting to
+/// multiple choice. Returns `None` if there is no more input.
+fn read_kind(inter: &mut dyn Interface) -> Option<Kind> {
     loop {
-        inter.write_stdout_line("Enter a new question:");
-        let Some(q_text) = inter.read_stdin_line() else {
-            return;
-        };
+        say(inter, "add-enter-kind");
+        let ans = inter.read_stdin_line()?;
+        if ans.trim().is_empty() {
+            return Some(Kind::MultipleChoice);
+        }
+        match Kind::from_str(ans.trim(), true) {
+            Ok(kind) => return Some(kind),
+            Err(_) => say_about(inter, "add-unknown-kind", "kind", ans.trim()),
+        }
+    }
+}
 
-        inter.write_stdout_line("Enter the correct answer:");
-        let Some(correct) = inter.read_stdin_line() else {
-            return;
-        };
+/// Asks for the incorrect answers of a question, refusing its `corrects`.
+fn read_incorrects(inter: &mut dyn Interface, corrects: &[String]) -> Option<Vec<String>> {
+    read_answers(
+        inter,
+        "add-enter-incorrects",
+        "add-incorrects-required",
+        corrects,
+    )
+}
 
-        let mut incorrects: Vec<String> = Vec::with_capacity(3);
-        let ordinals = &["first", "second", "third"];
+/// Keeps asking for answers with the message `prompt` until a blank line is
+/// entered, printing the message `required` if no answer was given yet. Refuses answers that were
+/// already given and the `corrects` answers.
+fn read_answers(
+    inter: &mut dyn Interface,
+    prompt: &str,
+    required: &str,
+    corrects: &[String],
+) -> Option<Vec<String>> {
+    let mut answers: Vec<String> = Vec::new();
+    loop {
+        say(inter, prompt);
+        let ans = inter.read_stdin_line()?;
 
-        for i in 0..3 {
-            inter.write_stdout_line(&format!("Enter the {} incorrect answer:", ordinals[i]));
-            if let Some(ans) = inter.read_stdin_line() {
-                incorrects.push(ans);
-            } else {
-                return;
-            };
+        if corrects.iter().any(|c| is_correct_text(&ans, c)) {
+            say_about(inter, "add-is-correct", "answer", ans.trim());
+        } else if answers.iter().any(|a| is_correct_text(&ans, a)) {
+            say_about(inter, "add-repeated", "answer", ans.trim());
+        } else if !ans.trim().is_empty() {
+            answers.push(ans);
+        } else if answers.is_empty() {
+            say(inter, required);
+        } else {
+            return Some(answers);
         }
+    }
+}
 
-        let new_question = Question {
-            text: q_text,
-            correct: correct,
-            incorrects: incorrects,
-        };
+/// Asks for the accepted answers of a free-text question, then for a pattern
+/// until a valid one is given. Either is optional, but not both.
+fn read_free_text(inter: &mut dyn Interface) -> Option<QuestionKind> {
+    let mut accepted: Vec<String> = Vec::new();
+    loop {
+        say(inter, "add-enter-accepted");
+        let ans = inter.read_stdin_line()?;
+        if ans.trim().is_empty() {
+            break;
+        }
+        if accepted.iter().any(|a| is_correct_text(&ans, a)) {
+            say_about(inter, "add-repeated", "answer", ans.trim());
+            continue;
+        }
+        accepted.push(ans);
+    }
 
-        quiz.questions.push(new_question);
+    loop {
+        say(inter, "add-enter-pattern");
+        let pattern = inter.read_stdin_line()?;
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            if accepted.is_empty() {
+                say(inter, "add-accepted-required");
+                continue;
+            }
+            return Some(QuestionKind::FreeText {
+                accepted,
+                pattern: None,
+            });
+        }
+        match compile_pattern(pattern) {
+            Ok(_) => {
+                return Some(QuestionKind::FreeText {
+                    accepted,
+                    pattern: Some(pattern.to_string()),
+                });
+            }
+            Err(e) => say_about(inter, "add-invalid-pattern", "error", &e.to_string()),
+        }
+    }
+}
 
-        inter.write_stdout_line("Question added!");
+/// Asks for the answer of a numeric question and how far off an answer can
+/// be, until valid numbers are given.
+fn read_numeric(inter: &mut dyn Interface) -> Option<QuestionKind> {
+    let answer = loop {
+        say(inter, "add-enter-number");
+        let ans = inter.read_stdin_line()?;
+        match ans.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => break n,
+            _ => say_about(inter, "add-not-a-number", "answer", ans.trim()),
+        }
+    };
 
-        inter.write_stdout_line("Continue adding questions? (y/n)");
-        if let Some(ans) = inter.read_stdin_line() {
-            if ans.trim().to_lowercase() != "y" {
-                let res = save_quiz(inter, &quiz);
-                if let Err(e) = res {
-                    inter.write_stdout_line(&format!("Failed to save quiz to 'quiz.json': {}", e));
-                }
+    let tolerance = loop {
+        say(inter, "add-enter-tolerance");
+        let ans = inter.read_stdin_line()?;
+        if ans.trim().is_empty() {
+            break 0.0;
+        }
+        match ans.trim().parse::<f64>() {
+            Ok(n) if n >= 0.0 => break n,
+            _ => say_about(inter, "add-bad-tolerance", "answer", ans.trim()),
+        }
+    };
+
+    Some(QuestionKind::Numeric { answer, tolerance })
+}
+
+/// Asks for the difficulty of a question until a valid one, or none, is given.
+/// Returns `None` if there is no more input.
+fn read_difficulty(inter: &mut dyn Interface) -> Option<Option<Difficulty>> {
+    loop {
+        say(inter, "add-enter-difficulty");
+        let ans = inter.read_stdin_line()?;
+        if ans.trim().is_empty() {
+            return Some(None);
+        }
+        match ans.parse() {
+            Ok(difficulty) => return Some(Some(difficulty)),
+            Err(_) => say_about(
+                inter,
+                "add-unknown-difficulty",
+                "difficulty",
+                &ans.trim().to_lowercase(),
+            ),
+        }
+    }
+}
+
+/// The answers of a question given on the command line, and what explains
+/// them. Which of the answers are used depends on the kind of question.
+#[derive(Debug, Default)]
+pub struct AnswerArgs<'a> {
+    pub kind: Kind,
+    /// The correct answer. For a free-text question this is an accepted
+    /// answer and for a numeric one the number.
+    pub correct: Option<&'a str>,
+    pub incorrects: &'a [String],
+    /// More correct answers of a multi-select question, or accepted answers
+    /// of a free-text question.
+    pub also_correct: &'a [String],
+    /// Give multi-select questions partial credit.
+    pub partial: bool,
+    /// A pattern free-text answers are accepted by.
+    pub pattern: Option<&'a str>,
+    /// How far off the answer to a numeric question can be.
+    pub tolerance: Option<f64>,
+    /// Why the correct answer is correct.
+    pub explanation: Option<&'a str>,
+    /// Where to read more about the answer.
+    pub reference: Option<&'a str>,
+}
 
-                break;
+impl AnswerArgs<'_> {
+    pub(crate) fn to_question_kind(&self) -> Result<QuestionKind, QuizError> {
+        // Options that do not apply to this kind of question are an error
+        // rather than silently ignored.
+        let unused = [
+            (
+                "--also-correct",
+                !self.also_correct.is_empty(),
+                [Kind::MultiSelect, Kind::FreeText].as_slice(),
+            ),
+            ("--partial", self.partial, &[Kind::MultiSelect]),
+            ("--pattern", self.pattern.is_some(), &[Kind::FreeText]),
+            ("--tolerance", self.tolerance.is_some(), &[Kind::Numeric]),
+        ]
+        .into_iter()
+        .find(|(_, given, kinds)| *given && !kinds.contains(&self.kind));
+        if let Some((option, _, _)) = unused {
+            return invalid(
+                Message::new("add-unused-option")
+                    .with("option", option)
+                    .with("kind", self.kind.to_string()),
+            );
+        }
+
+        if matches!(self.kind, Kind::FreeText | Kind::Numeric) && !self.incorrects.is_empty() {
+            return invalid(Message::new("add-no-incorrects").with("kind", self.kind.to_string()));
+        }
+
+        let correct = || match self.correct {
+            Some(correct) => Ok(correct),
+            None => invalid(Message::new("add-missing-correct")),
+        };
+        let incorrects = || {
+            if self.incorrects.is_empty() {
+                invalid(Message::new("add-incorrects-required"))
             } else {
-                continue;
+                Ok(self.incorrects.to_vec())
+            }
+        };
+
+        match self.kind {
+            Kind::MultipleChoice => Ok(QuestionKind::MultipleChoice {
+                correct: correct()?.to_string(),
+                incorrects: incorrects()?,
+            }),
+            Kind::MultiSelect => Ok(QuestionKind::MultiSelect {
+                corrects: std::iter::once(correct()?.to_string())
+                    .chain(self.also_correct.iter().cloned())
+                    .collect(),
+                incorrects: incorrects()?,
+                partial: self.partial,
+            }),
+            Kind::FreeText => Ok(QuestionKind::FreeText {
+                accepted: self
+                    .correct
+                    .iter()
+                    .map(|a| a.to_string())
+                    .chain(self.also_correct.iter().cloned())
+                    .collect(),
+                pattern: self.pattern.map(String::from),
+            }),
+            Kind::Numeric => {
+                let correct = correct()?;
+                match correct.trim().parse() {
+                    Ok(answer) => Ok(QuestionKind::Numeric {
+                        answer,
+                        tolerance: self.tolerance.unwrap_or_default(),
+                    }),
+                    Err(_) => {
+                        invalid(Message::new("add-correct-not-a-number").with("answer", correct))
+                    }
+                }
             }
-        } else {
-            return;
         }
     }
 }
 
+fn invalid<T>(reason: Message) -> Result<T, QuizError> {
+    Err(QuizError::InvalidQuestion(reason))
+}
+
+/// Adds a single question to the quiz without prompting.
+/// Used by `quizzer add <QUESTION> <CORRECT_ANSWER> <INCORRECT_ANSWERS>...`.
+pub fn add_question(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    text: &str,
+    answers: &AnswerArgs,
+    tags: &[String],
+    difficulty: Option<Difficulty>,
+) -> Result<(), QuizError> {
+    let kind = answers.to_question_kind()?;
+
+    if text.trim().is_empty() {
+        return invalid(Message::new("add-empty-question"));
+    }
+    if let Some(problem) = kind.problem() {
+        return invalid(Message::new("add-invalid").with("problem", problem));
+    }
+    let question = Question {
+        text: text.to_string(),
+        kind,
+        tags: tags.to_vec(),
+        difficulty,
+        explanation: answers.explanation.map(String::from),
+        reference: answers.reference.map(String::from),
+    };
+    if let Some((problem, _)) = check_question(&question).into_iter().next() {
+        return invalid(Message::new("add-invalid").with("problem", problem));
+    }
+
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    match find_duplicate(&quiz.questions, &question) {
+        Some(Duplicate::Same(idx)) => {
+            return invalid(Message::new("add-already").with("number", idx + 1));
+        }
+        Some(Duplicate::Similar(idx)) => warn_similar(inter, idx, &quiz.questions[idx]),
+        None => {}
+    }
+    update_quiz(store, quiz_file, |quiz| {
+        quiz.questions.push(question.clone());
+        Ok(())
+    })
+    .map_err(|source| QuizError::Save {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    say(inter, "add-added");
+    Ok(())
+}
+
+/// Writes the message `id`.
+fn say(inter: &mut dyn Interface, id: &str) {
+    let msg = inter.messages().get(id);
+    inter.write_stdout_line(&msg);
+}
+
+/// Writes the message `id`, about the `value` of its one argument `arg`.
+fn say_about(inter: &mut dyn Interface, id: &str, arg: &str, value: &str) {
+    let msg = inter.messages().format(id, &[(arg, value.into())]);
+    inter.write_stdout_line(&msg);
+}
+
+fn warn_similar(inter: &mut dyn Interface, idx: usize, similar: &Question) {
+    let msg = inter.messages().format(
+        "add-similar",
+        &[
+            ("number", (idx + 1).into()),
+            ("text", similar.text.as_str().into()),
+        ],
+    );
+    inter.write_stdout_line(&msg);
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
 
     use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
 
     use super::*;
 
@@ -77,28 +480,97 @@ mod tests {
         let mut inter = FakeInterface::with_stdin(
             "\
             What's my name?\n\
+            \n\
             quizzer\n\
             andy\n\
             sam\n\
             joe\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            n\n\
+        ",
+        );
+        let mut store = MemoryStore::new();
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Enter a new question:\n\
+            Enter the kind of question: multiple-choice, multi-select, free-text or numeric (leave blank for multiple-choice):\n\
+            Enter the correct answer:\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter tags, separated by commas (leave blank for none):\n\
+            Enter the difficulty: easy, medium or hard (leave blank for none):\n\
+            Enter an explanation of the answer (leave blank for none):\n\
+            Enter a reference to read more, such as a URL or chapter (leave blank for none):\n\
+            Question added!\n\
+            Continue adding questions? (y/n)\n\
+            "
+        );
+    }
+
+    #[test]
+    fn add_requires_at_least_one_incorrect_answer() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            Is Rust memory safe?\n\
+            \n\
+            true\n\
+            \n\
+            false\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
             n\n\
         ",
         );
+        let mut store = MemoryStore::new();
 
-        add(&mut inter);
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
 
         assert_eq!(
             inter.stdout(),
             "\
             Enter a new question:\n\
+            Enter the kind of question: multiple-choice, multi-select, free-text or numeric (leave blank for multiple-choice):\n\
             Enter the correct answer:\n\
-            Enter the first incorrect answer:\n\
-            Enter the second incorrect answer:\n\
-            Enter the third incorrect answer:\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            At least one incorrect answer is required.\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter tags, separated by commas (leave blank for none):\n\
+            Enter the difficulty: easy, medium or hard (leave blank for none):\n\
+            Enter an explanation of the answer (leave blank for none):\n\
+            Enter a reference to read more, such as a URL or chapter (leave blank for none):\n\
             Question added!\n\
             Continue adding questions? (y/n)\n\
             "
         );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&store.quiz_file())
+                .expect("Failed to deserialize quiz file"),
+            json!({
+                "version": 3,
+                "questions": [
+                    {
+                        "text": "Is Rust memory safe?",
+                        "kind": "multiple_choice",
+                        "correct": "true",
+                        "incorrects": ["false"],
+                    }
+                ]
+            })
+        );
     }
 
     #[test]
@@ -106,39 +578,559 @@ mod tests {
         let mut inter = FakeInterface::with_stdin(
             "\
             What's my name?\n\
+            \n\
             quizzer\n\
             andy\n\
             sam\n\
             joe\n\
+            \n\
+            names, people\n\
+            Easy\n\
+            It says so on the box.\n\
+              README.md  \n\
             y\n\
             What is 2 + 2?\n\
+            \n\
             4\n\
             3\n\
             5\n\
             0\n\
+            1\n\
+            \n\
+            maths\n\
+            impossible\n\
+            hard\n\
+            \n\
+            \n\
             n\n\
         ",
         );
+        let mut store = MemoryStore::new();
 
-        add(&mut inter);
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
 
         assert_eq!(
-            serde_json::from_str::<serde_json::Value>(&inter.quiz_file())
+            serde_json::from_str::<serde_json::Value>(&store.quiz_file())
                 .expect("Failed to deserialize quiz file"),
             json!({
+                "version": 3,
                 "questions": [
                     {
                         "text": "What's my name?",
+                        "kind": "multiple_choice",
                         "correct": "quizzer",
                         "incorrects": ["andy", "sam", "joe"],
+                        "tags": ["names", "people"],
+                        "difficulty": "easy",
+                        "explanation": "It says so on the box.",
+                        "reference": "README.md",
                     },
                     {
                         "text": "What is 2 + 2?",
+                        "kind": "multiple_choice",
                         "correct": "4",
-                        "incorrects": ["3", "5", "0"],
+                        "incorrects": ["3", "5", "0", "1"],
+                        "tags": ["maths"],
+                        "difficulty": "hard",
                     }
                 ]
             })
         );
     }
+
+    #[test]
+    fn add_question_appends_to_existing_quiz() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[{"text":"What's my name?","correct":"quizzer","incorrects":["andy"]}]}"#,
+        );
+
+        let incorrects = ["3".to_string(), "5".to_string()];
+        let answers = AnswerArgs {
+            correct: Some("4"),
+            incorrects: &incorrects,
+            ..Default::default()
+        };
+        add_question(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            "What is 2 + 2?",
+            &answers,
+            &["maths".to_string()],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(inter.stdout(), "Question added!\n");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&store.quiz_file())
+                .expect("Failed to deserialize quiz file"),
+            json!({
+                "version": 3,
+                "questions": [
+                    {
+                        "text": "What's my name?",
+                        "kind": "multiple_choice",
+                        "correct": "quizzer",
+                        "incorrects": ["andy"],
+                    },
+                    {
+                        "text": "What is 2 + 2?",
+                        "kind": "multiple_choice",
+                        "correct": "4",
+                        "incorrects": ["3", "5"],
+                        "tags": ["maths"],
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn add_question_requires_an_incorrect_answer() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        let answers = AnswerArgs {
+            correct: Some("4"),
+            ..Default::default()
+        };
+        let err = add_question(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            "What is 2 + 2?",
+            &answers,
+            &[],
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, QuizError::InvalidQuestion(_)));
+        assert_eq!(
+            err.to_string(),
+            "At least one incorrect answer is required."
+        );
+        assert_eq!(inter.stdout(), "");
+        assert!(store.saved_quiz_file().is_none());
+    }
+
+    #[test]
+    fn add_saves_completed_questions_when_input_ends() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            What is 2 + 2?\n\
+            \n\
+            4\n\
+            3\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            y\n\
+            What is 3 + 3?\n\
+            \n\
+            6\n\
+        ",
+        );
+        let mut store = MemoryStore::new();
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&store.quiz_file())
+                .expect("Failed to deserialize quiz file"),
+            json!({
+                "version": 3,
+                "questions": [
+                    { "text": "What is 2 + 2?", "kind": "multiple_choice", "correct": "4", "incorrects": ["3"] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn add_without_input_does_not_save() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert!(store.saved_quiz_file().is_none());
+    }
+
+    #[test]
+    fn add_reports_failed_read() {
+        let mut inter = FakeInterface::with_stdin("What is 2 + 2?\n")
+            .with_stdin_error(std::io::ErrorKind::BrokenPipe);
+        let mut store = MemoryStore::new();
+
+        let err = add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap_err();
+
+        assert!(matches!(err, QuizError::Io(_)));
+        assert!(store.saved_quiz_file().is_none());
+    }
+
+    #[test]
+    fn add_asks_for_answers_of_each_kind() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            Which are primes?\n\
+            multi-select\n\
+            2\n\
+            3\n\
+            \n\
+            4\n\
+            \n\
+            y\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            y\n\
+            Capital of France?\n\
+            Free-Text\n\
+            Paris\n\
+            \n\
+            (\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            y\n\
+            What is 1/3?\n\
+            numeric\n\
+            pi\n\
+            0.33\n\
+            0.01\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            n\n\
+        ",
+        );
+        let mut store = MemoryStore::new();
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert!(inter.stdout().contains("Invalid regular expression: "));
+        assert!(inter.stdout().contains("'pi' is not a number.\n"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&store.quiz_file())
+                .expect("Failed to deserialize quiz file")["questions"],
+            json!([
+                {
+                    "text": "Which are primes?",
+                    "kind": "multi_select",
+                    "corrects": ["2", "3"],
+                    "incorrects": ["4"],
+                    "partial": true,
+                },
+                {
+                    "text": "Capital of France?",
+                    "kind": "free_text",
+                    "accepted": ["Paris"],
+                },
+                {
+                    "text": "What is 1/3?",
+                    "kind": "numeric",
+                    "answer": 0.33,
+                    "tolerance": 0.01,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn add_question_builds_each_kind() {
+        let also_correct = ["Lutetia".to_string()];
+        let cases = [
+            (
+                AnswerArgs {
+                    kind: Kind::FreeText,
+                    correct: Some("Paris"),
+                    also_correct: &also_correct,
+                    ..Default::default()
+                },
+                json!({ "kind": "free_text", "accepted": ["Paris", "Lutetia"] }),
+            ),
+            (
+                AnswerArgs {
+                    kind: Kind::FreeText,
+                    pattern: Some("paris|lutetia"),
+                    ..Default::default()
+                },
+                json!({ "kind": "free_text", "pattern": "paris|lutetia" }),
+            ),
+            (
+                AnswerArgs {
+                    kind: Kind::Numeric,
+                    correct: Some("42"),
+                    tolerance: Some(0.5),
+                    ..Default::default()
+                },
+                json!({ "kind": "numeric", "answer": 42.0, "tolerance": 0.5 }),
+            ),
+        ];
+
+        for (answers, expected) in cases {
+            let mut inter = FakeInterface::new();
+            let mut store = MemoryStore::new();
+
+            add_question(
+                &mut inter,
+                &mut store,
+                DEFAULT_QUIZ_FILE,
+                "Q?",
+                &answers,
+                &[],
+                None,
+            )
+            .unwrap();
+
+            let mut saved: serde_json::Value =
+                serde_json::from_str(&store.quiz_file()).expect("Failed to deserialize quiz file");
+            let question = saved["questions"][0].as_object_mut().unwrap();
+            question.remove("text");
+            assert_eq!(serde_json::Value::Object(question.clone()), expected);
+        }
+    }
+
+    #[test]
+    fn add_question_rejects_options_of_other_kinds() {
+        let incorrects = ["3".to_string()];
+        let cases = [
+            (
+                AnswerArgs {
+                    correct: Some("4"),
+                    incorrects: &incorrects,
+                    pattern: Some("4|four"),
+                    ..Default::default()
+                },
+                "--pattern cannot be used with multiple-choice questions.",
+            ),
+            (
+                AnswerArgs {
+                    kind: Kind::Numeric,
+                    correct: Some("4"),
+                    incorrects: &incorrects,
+                    ..Default::default()
+                },
+                "Numeric questions have no incorrect answers.",
+            ),
+            (
+                AnswerArgs {
+                    kind: Kind::Numeric,
+                    correct: Some("four"),
+                    ..Default::default()
+                },
+                "The correct answer 'four' is not a number.",
+            ),
+            (
+                AnswerArgs {
+                    kind: Kind::FreeText,
+                    ..Default::default()
+                },
+                "At least one accepted answer or a pattern is required.",
+            ),
+        ];
+
+        for (answers, message) in cases {
+            let mut inter = FakeInterface::new();
+            let mut store = MemoryStore::new();
+
+            let err = add_question(
+                &mut inter,
+                &mut store,
+                DEFAULT_QUIZ_FILE,
+                "Q?",
+                &answers,
+                &[],
+                None,
+            )
+            .unwrap_err();
+
+            assert_eq!(err.to_string(), message);
+            assert!(store.saved_quiz_file().is_none());
+        }
+    }
+
+    #[test]
+    fn add_keeps_questions_added_meanwhile() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            What is 2 + 2?\n\
+            \n\
+            4\n\
+            3\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            n\n\
+        ",
+        );
+        let mut store = MemoryStore::new().with_change_before_update(DEFAULT_QUIZ_FILE,
+            r#"{"version":2,"questions":[{"text":"True?","kind":"multiple_choice","correct":"yes","incorrects":["no"]}]}"#,
+        );
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&store.quiz_file()).expect("Failed to deserialize quiz file");
+        assert_eq!(saved["questions"][0]["text"], "True?");
+        assert_eq!(saved["questions"][1]["text"], "What is 2 + 2?");
+    }
+
+    #[test]
+    fn add_refuses_empty_and_repeated_answers() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            \n\
+            Is Rust memory safe?\n\
+            \n\
+            \n\
+            true\n\
+            True\n\
+            false\n\
+            false \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            n\n\
+        ",
+        );
+        let mut store = MemoryStore::new();
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Enter a new question:\n\
+            The question cannot be empty.\n\
+            Enter a new question:\n\
+            Enter the kind of question: multiple-choice, multi-select, free-text or numeric (leave blank for multiple-choice):\n\
+            Enter the correct answer:\n\
+            The correct answer cannot be empty.\n\
+            Enter the correct answer:\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            'True' is a correct answer.\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            'false' was already given.\n\
+            Enter an incorrect answer (leave blank to finish):\n\
+            Enter tags, separated by commas (leave blank for none):\n\
+            Enter the difficulty: easy, medium or hard (leave blank for none):\n\
+            Enter an explanation of the answer (leave blank for none):\n\
+            Enter a reference to read more, such as a URL or chapter (leave blank for none):\n\
+            Question added!\n\
+            Continue adding questions? (y/n)\n\
+            "
+        );
+        let saved: serde_json::Value =
+            serde_json::from_str(&store.quiz_file()).expect("Failed to deserialize quiz file");
+        assert_eq!(saved["questions"][0]["incorrects"], json!(["false"]));
+    }
+
+    #[test]
+    fn add_warns_of_similar_and_skips_same_questions() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            What is 2+2\n\
+            \n\
+            4\n\
+            3\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            y\n\
+            What is 2 + 3?\n\
+            \n\
+            5\n\
+            4\n\
+            \n\
+            \n\
+            \n\
+            \n\
+            \n\
+            n\n\
+        ",
+        );
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"version":2,"questions":[{"text":"What is 2 + 2?","kind":"multiple_choice","correct":"4","incorrects":["3"]}]}"#,
+        );
+
+        add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert!(
+            inter.stdout().contains(
+                "The quiz already has this question as question 1, so it was not added.\n"
+            )
+        );
+        assert!(
+            inter
+                .stdout()
+                .contains("Warning: this looks like question 1: What is 2 + 2?\n")
+        );
+        let saved: serde_json::Value =
+            serde_json::from_str(&store.quiz_file()).expect("Failed to deserialize quiz file");
+        assert_eq!(saved["questions"].as_array().unwrap().len(), 2);
+        assert_eq!(saved["questions"][1]["text"], "What is 2 + 3?");
+    }
+
+    #[test]
+    fn add_question_rejects_repeated_answers_and_duplicates() {
+        let quiz = r#"{"version":2,"questions":[{"text":"What is 2 + 2?","kind":"multiple_choice","correct":"4","incorrects":["3"]}]}"#;
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+        let cases = [
+            (
+                "What is 3 + 3?",
+                vec!["5", "5"],
+                "The incorrect answer '5' is repeated.",
+            ),
+            (
+                "What is 3 + 3?",
+                vec!["5", " 6"],
+                "The incorrect answer ' 6' is also a correct answer.",
+            ),
+            (
+                "what is 2+2",
+                vec!["3"],
+                "The quiz already has this question as question 1.",
+            ),
+        ];
+
+        for (text, incorrects, message) in cases {
+            let incorrects: Vec<String> = incorrects.into_iter().map(String::from).collect();
+            let answers = AnswerArgs {
+                correct: Some(if text.contains('3') { "6" } else { "4" }),
+                incorrects: &incorrects,
+                ..Default::default()
+            };
+            let err = add_question(
+                &mut inter,
+                &mut store,
+                DEFAULT_QUIZ_FILE,
+                text,
+                &answers,
+                &[],
+                None,
+            )
+            .unwrap_err();
+
+            assert_eq!(err.to_string(), message);
+        }
+        assert_eq!(store.quiz_file(), quiz);
+    }
 }
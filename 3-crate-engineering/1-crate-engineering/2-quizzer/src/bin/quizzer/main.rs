@@ -1,48 +1,411 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+use std::time::Duration;
+
 use clap::{Args, Parser, Subcommand};
-use quizzer::add::add;
+use quizzer::add::{AnswerArgs, add, add_question};
+use quizzer::check::check;
+use quizzer::convert::{Format, export, import};
+use quizzer::edit::{edit_question, remove_question};
+use quizzer::error::QuizError;
+use quizzer::file_store::FileStore;
+use quizzer::grade::grade;
+use quizzer::i18n::Lang;
 use quizzer::interface::Interface;
-use quizzer::quiz::run_quiz;
+use quizzer::kind::Kind;
+use quizzer::list::{list_questions, list_quizzes};
+use quizzer::quiz::{DEFAULT_QUIZ_FILE, Difficulty, QuizOptions, run_quiz};
 use quizzer::real_interface::RealInterface;
+use quizzer::results::ResultsFormat;
+use quizzer::seal::export_sealed;
+use quizzer::serve::serve;
+use quizzer::sqlite_store::SqliteStore;
+use quizzer::stats::stats;
+use quizzer::store::QuizStore;
+use quizzer::study::study;
+use quizzer::tui_interface::TuiInterface;
 
 #[derive(Parser)]
 #[command(version)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// The quiz file to use
+    #[arg(long, global = true, default_value = DEFAULT_QUIZ_FILE)]
+    file: String,
+
+    /// Seed for shuffling the answer choices, to give everyone the same quiz
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+
+    /// Keep the last N versions of the quiz file when changing it, as
+    /// FILE.bak.1 (the newest) to FILE.bak.N. Only .json files are backed up
+    #[arg(long, global = true, value_name = "N", default_value_t = 0)]
+    backups: usize,
+
+    /// Keep the quizzes in this SQLite database instead of in files, with
+    /// --file naming the quiz in the database
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "backups")]
+    db: Option<String>,
+
+    /// Show questions in a terminal UI, picking answers with the arrow keys
+    #[arg(long, global = true)]
+    tui: bool,
+
+    /// The language of the messages, by default taken from LC_ALL,
+    /// LC_MESSAGES or LANG
+    #[arg(long, global = true)]
+    lang: Option<Lang>,
+
+    /// The secret to seal a quiz with, and to check the answers of a quiz
+    /// sealed with it when running, serving, studying or grading it
+    #[arg(long, global = true, env = "QUIZZER_KEY", hide_env_values = true)]
+    key: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Adds questions to the quiz
-    // Add(AddArgs),
-    Add,
+    ///
+    /// Prompts for questions interactively unless a question is given.
+    Add(AddArgs),
 
     /// Runs the quiz
-    Quiz,
+    Quiz(QuizArgs),
+
+    /// Studies the questions that are due, using spaced repetition
+    Study {
+        /// The most questions to study in this session
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// Lists the numbered questions of the quiz
+    List {
+        /// Lists the quizzes in a directory instead, by default the current one
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+        quizzes: Option<String>,
+    },
+
+    /// Edits a question, showing the current values
+    Edit {
+        /// The number of the question, as shown by list
+        number: usize,
+    },
+
+    /// Removes a question
+    Remove {
+        /// The number of the question, as shown by list
+        number: usize,
+    },
+
+    /// Imports questions from a CSV, YAML or Markdown file into the quiz
+    Import {
+        /// The file to import questions from
+        path: String,
+        /// The format of the file, if it cannot be told from its extension
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Prints the quiz as CSV, YAML or Markdown, or sealed to hand out
+    Export {
+        /// The format to print the quiz in
+        #[arg(long, value_enum, required_unless_present = "sealed")]
+        format: Option<Format>,
+        /// Print the quiz as JSON with the correct answers hashed, so that it
+        /// can be run and graded without showing them.
+        ///
+        /// Anyone with the file can still check guesses against the hashes,
+        /// so without --key this only hides answers that are hard to guess:
+        /// multiple-choice, multi-select and numeric questions are refused.
+        /// With --key, the answers can only be checked with the key. A quiz
+        /// sealed with a key can be run without it with --results, which
+        /// records the answers for grade --key to score.
+        #[arg(long, conflicts_with = "format")]
+        sealed: bool,
+    },
+
+    /// Serves the quiz over HTTP, for a room of students to take it
+    ///
+    /// Each student starts a session with POST /sessions, then fetches
+    /// questions with GET /sessions/ID/question, answers them with
+    /// POST /sessions/ID/answer and checks the score with
+    /// GET /sessions/ID/score.
+    Serve(ServeArgs),
+
+    /// Shows how the quiz went across runs: scores over time, how often each
+    /// question is answered right and which are missed most
+    Stats,
+
+    /// Checks the quiz for empty or contradictory answers and duplicate questions
+    Check {
+        /// Fixes the problems that can be fixed, such as repeated answers
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Grades answer sheets without prompting and prints a summary
+    Grade {
+        /// Answer sheets with the full text of one answer per line, or .json
+        /// results written by quiz --results, one per student
+        #[arg(long, required = true, num_args = 1.., value_name = "FILE")]
+        answers: Vec<String>,
+    },
 }
 
-#[derive(Args)]
+#[derive(Args, Default)]
 struct AddArgs {
-    question: String,
-    correct_answer: String,
+    /// The question to add
+    question: Option<String>,
+    /// The correct answer to the question: an accepted answer of a free-text
+    /// question, or the number answering a numeric one
+    correct_answer: Option<String>,
+    /// One or more incorrect answers to the question
     incorrect_answers: Vec<String>,
+    /// The kind of question
+    #[arg(long, value_enum, default_value_t)]
+    kind: Kind,
+    /// Another correct answer of a multi-select question, or accepted answer of
+    /// a free-text one, can be given more than once
+    #[arg(long, value_name = "ANSWER")]
+    also_correct: Vec<String>,
+    /// Give partial credit for picking some of a multi-select question's correct answers
+    #[arg(long)]
+    partial: bool,
+    /// A regular expression accepting the answers to a free-text question
+    #[arg(long, value_name = "REGEX")]
+    pattern: Option<String>,
+    /// How far off the answer to a numeric question can be
+    #[arg(long)]
+    tolerance: Option<f64>,
+    /// A tag for the question, can be given more than once
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// How difficult the question is
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+    /// Why the correct answer is correct, shown after the question is answered
+    #[arg(long)]
+    explanation: Option<String>,
+    /// Where to read more about the answer, such as a URL or chapter
+    #[arg(long)]
+    reference: Option<String>,
+}
+
+#[derive(Args, Default)]
+struct QuizArgs {
+    /// Only ask questions with this tag, can be given more than once
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Only ask questions of this difficulty
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+    /// Ask at most this many questions
+    #[arg(long)]
+    limit: Option<usize>,
+    /// Ask the questions in a random order
+    #[arg(long)]
+    shuffle: bool,
+    /// The time allowed for the whole quiz, in seconds
+    #[arg(long, value_name = "SECONDS")]
+    time_limit: Option<u64>,
+    /// The time allowed for each question, in seconds
+    #[arg(long, value_name = "SECONDS")]
+    per_question_timeout: Option<u64>,
+    /// Write a record of every answer to this file, for automatic grading
+    #[arg(long, value_name = "PATH")]
+    results: Option<String>,
+    /// The format of the results file [default: junit for .xml files, json otherwise]
+    #[arg(long, value_enum, requires = "results")]
+    results_format: Option<ResultsFormat>,
+    /// List every missed question with its correct answer at the end
+    #[arg(long)]
+    review: bool,
+    /// Play with several players taking turns, separated by commas
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "NAMES",
+        conflicts_with_all = ["time_limit", "results", "review"]
+    )]
+    players: Vec<String>,
+    /// Ask every player each question, the first right answer wins the point
+    #[arg(long, requires = "players")]
+    first_correct: bool,
 }
 
-fn run(cli: Cli, inter: &mut dyn Interface) {
+#[derive(Args)]
+struct ServeArgs {
+    /// The address to listen on, 0.0.0.0 to let in students on other computers
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    /// The port to listen on
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+    /// Only ask questions with this tag, can be given more than once
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Only ask questions of this difficulty
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+    /// Ask at most this many questions
+    #[arg(long)]
+    limit: Option<usize>,
+    /// Ask the questions in a random order, different for every student
+    #[arg(long)]
+    shuffle: bool,
+}
+
+fn run(cli: Cli, inter: &mut dyn Interface, store: &mut dyn QuizStore) -> Result<(), QuizError> {
+    let quiz_file = &cli.file;
     match &cli.command {
-        Some(Commands::Add) => {
-            add(inter);
+        Some(Commands::Add(args)) => match &args.question {
+            Some(question) => {
+                let answers = AnswerArgs {
+                    kind: args.kind,
+                    correct: args.correct_answer.as_deref(),
+                    incorrects: &args.incorrect_answers,
+                    also_correct: &args.also_correct,
+                    partial: args.partial,
+                    pattern: args.pattern.as_deref(),
+                    tolerance: args.tolerance,
+                    explanation: args.explanation.as_deref(),
+                    reference: args.reference.as_deref(),
+                };
+                add_question(
+                    inter,
+                    store,
+                    quiz_file,
+                    question,
+                    &answers,
+                    &args.tags,
+                    args.difficulty,
+                )
+            }
+            None => add(inter, store, quiz_file),
+        },
+        Some(Commands::Quiz(args)) => {
+            let options = QuizOptions {
+                tags: args.tags.clone(),
+                difficulty: args.difficulty,
+                limit: args.limit,
+                shuffle: args.shuffle,
+                time_limit: args.time_limit.map(Duration::from_secs),
+                per_question_timeout: args.per_question_timeout.map(Duration::from_secs),
+                results: args.results.clone(),
+                results_format: args.results_format,
+                review: args.review,
+                players: args.players.clone(),
+                first_correct: args.first_correct,
+                key: cli.key.clone(),
+            };
+            run_quiz(inter, store, quiz_file, &options)
+        }
+        Some(Commands::Study { limit }) => {
+            study(inter, store, quiz_file, *limit, cli.key.as_deref())
+        }
+        Some(Commands::List { quizzes }) => match quizzes {
+            Some(dir) => list_quizzes(inter, store, dir),
+            None => list_questions(inter, store, quiz_file),
+        },
+        Some(Commands::Edit { number }) => edit_question(inter, store, quiz_file, *number),
+        Some(Commands::Remove { number }) => remove_question(inter, store, quiz_file, *number),
+        Some(Commands::Import { path, format }) => import(inter, store, quiz_file, path, *format),
+        Some(Commands::Export { format, sealed }) => match format {
+            Some(format) if !sealed => export(inter, store, quiz_file, *format),
+            _ => export_sealed(inter, store, quiz_file, cli.key.as_deref()),
+        },
+        Some(Commands::Serve(args)) => {
+            let options = QuizOptions {
+                tags: args.tags.clone(),
+                difficulty: args.difficulty,
+                limit: args.limit,
+                shuffle: args.shuffle,
+                key: cli.key.clone(),
+                ..Default::default()
+            };
+            serve(inter, store, quiz_file, &args.host, args.port, options)
+        }
+        Some(Commands::Stats) => stats(inter, store, quiz_file),
+        Some(Commands::Check { fix }) => check(inter, store, quiz_file, *fix),
+        Some(Commands::Grade { answers }) => {
+            grade(inter, store, quiz_file, answers, cli.key.as_deref())
+        }
+        &None => {
+            let msg = inter.messages().get("try-help");
+            inter.write_stdout_line(&msg);
+            Ok(())
         }
-        Some(Commands::Quiz) => run_quiz(inter),
-        &None => inter.write_stdout_line("Try ./quizzer --help"),
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    let mut inter = RealInterface::new();
+    let lang = cli.lang.or_else(locale_lang).unwrap_or_default();
+    let mut real = RealInterface::new().with_lang(lang);
+    if let Some(seed) = cli.seed {
+        real = real.with_seed(seed);
+    }
+
+    let mut tui;
+    let inter: &mut dyn Interface = if cli.tui {
+        if !io::stdin().is_terminal() {
+            eprintln!("{}", real.messages().get("tui-needs-terminal"));
+            return ExitCode::FAILURE;
+        }
+        tui = TuiInterface::new(real);
+        &mut tui
+    } else {
+        &mut real
+    };
+
+    let mut files;
+    let mut db;
+    let store: &mut dyn QuizStore = match &cli.db {
+        Some(path) => match SqliteStore::open(path) {
+            Ok(opened) => {
+                db = opened;
+                &mut db
+            }
+            Err(e) => {
+                let msg = inter.messages().format(
+                    "db-failed",
+                    &[
+                        ("file", path.as_str().into()),
+                        ("error", e.to_string().into()),
+                    ],
+                );
+                eprintln!("{}", msg);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => {
+            files = FileStore::new().with_backups(cli.backups);
+            &mut files
+        }
+    };
+
+    match run(cli, inter, store) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // stdout may be what failed, so errors go to stderr.
+            eprintln!("{}", e.text(inter.messages()));
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    run(cli, &mut inter);
+/// Picks the language of the messages from the locale, which is set by the
+/// first of these variables that is not empty.
+fn locale_lang() -> Option<Lang> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .and_then(|locale| Lang::from_locale(&locale))
 }
 
 #[cfg(test)]
@@ -50,27 +413,119 @@ mod tests {
     use super::*;
 
     use quizzer::fake_interface::FakeInterface;
+    use quizzer::memory_store::MemoryStore;
 
     #[test]
     fn add_asks_for_question() {
         let cli = Cli {
-            command: Some(Commands::Add),
+            command: Some(Commands::Add(AddArgs::default())),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
         };
         let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
 
-        run(cli, &mut inter);
+        run(cli, &mut inter, &mut store).unwrap();
 
         assert_eq!(inter.stdout(), "Enter a new question:\n");
     }
 
+    #[test]
+    fn add_with_args_does_not_prompt() {
+        let cli = Cli {
+            command: Some(Commands::Add(AddArgs {
+                question: Some("What is 2 + 2?".to_string()),
+                correct_answer: Some("4".to_string()),
+                incorrect_answers: vec!["3".to_string(), "5".to_string(), "0".to_string()],
+                ..Default::default()
+            })),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
+        };
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        run(cli, &mut inter, &mut store).unwrap();
+
+        assert_eq!(inter.stdout(), "Question added!\n");
+        assert!(store.quiz_file().contains("What is 2 + 2?"));
+    }
+
+    #[test]
+    fn add_with_args_requires_correct_answer() {
+        let cli = Cli {
+            command: Some(Commands::Add(AddArgs {
+                question: Some("What is 2 + 2?".to_string()),
+                ..Default::default()
+            })),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
+        };
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        let err = run(cli, &mut inter, &mut store).unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing the correct answer.");
+        assert_eq!(inter.stdout(), "");
+    }
+
+    #[test]
+    fn remove_fails_for_missing_question() {
+        let cli = Cli {
+            command: Some(Commands::Remove { number: 2 }),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
+        };
+        let mut inter = FakeInterface::new();
+        let quiz = r#"{"questions":[{"text":"True?","correct":"yes","incorrects":["no"]}]}"#;
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+        let err = run(cli, &mut inter, &mut store).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "There is no question 2. The quiz has 1 questions, see ./quizzer list"
+        );
+        assert_eq!(store.quiz_file(), quiz);
+    }
+
     #[test]
     fn quiz_says_no_questions() {
         let cli = Cli {
-            command: Some(Commands::Quiz),
+            command: Some(Commands::Quiz(QuizArgs::default())),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
         };
         let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
 
-        run(cli, &mut inter);
+        run(cli, &mut inter, &mut store).unwrap();
 
         assert_eq!(
             inter.stdout(),
@@ -81,12 +536,86 @@ mod tests {
     #[test]
     fn quiz_asks_question() {
         let cli = Cli {
-            command: Some(Commands::Quiz),
+            command: Some(Commands::Quiz(QuizArgs::default())),
+            file: DEFAULT_QUIZ_FILE.to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
+        };
+        let mut inter = FakeInterface::with_stdin("d\n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[{"text":"What's my name?","correct":"quizzer","incorrects":["andy","sam","joe"]}]}"#,
+        );
+
+        run(cli, &mut inter, &mut store).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            What's my name?\n \
+            a) andy\n \
+            b) sam\n \
+            c) joe\n \
+            d) quizzer\n\
+            >>> Correct!\n\
+            \n\
+            You scored 1 out of 1 points.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn file_option_selects_quiz() {
+        let cli = Cli {
+            command: Some(Commands::Add(AddArgs {
+                question: Some("What is 2 + 2?".to_string()),
+                correct_answer: Some("4".to_string()),
+                incorrect_answers: vec!["3".to_string()],
+                ..Default::default()
+            })),
+            file: "maths.json".to_string(),
+            seed: None,
+            backups: 0,
+            db: None,
+            lang: None,
+            tui: false,
+            key: None,
         };
         let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        run(cli, &mut inter, &mut store).unwrap();
 
-        run(cli, &mut inter);
+        assert!(store.saved_quiz_file().is_none());
+        assert!(store.file("maths.json").unwrap().contains("What is 2 + 2?"));
+    }
+
+    #[test]
+    fn list_shows_questions_unless_listing_quizzes() {
+        let quiz = r#"{"questions":[{"text":"True?","correct":"yes","incorrects":["no"]}]}"#;
+        let list = |quizzes: Option<&str>| {
+            let cli = Cli {
+                command: Some(Commands::List {
+                    quizzes: quizzes.map(String::from),
+                }),
+                file: DEFAULT_QUIZ_FILE.to_string(),
+                seed: None,
+                backups: 0,
+                db: None,
+                lang: None,
+                key: None,
+                tui: false,
+            };
+            let mut inter = FakeInterface::new();
+            let mut store = MemoryStore::new().with_quiz_file(quiz);
+            run(cli, &mut inter, &mut store).unwrap();
+            inter.stdout().to_string()
+        };
 
-        assert_eq!(inter.stdout(), "What's my name?\n");
+        assert_eq!(list(None), "1. True?\n   [x] yes\n   [ ] no\n");
+        assert_eq!(list(Some(".")), "quiz.json (1 questions)\n");
     }
 }
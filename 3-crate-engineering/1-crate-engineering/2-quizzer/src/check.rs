@@ -0,0 +1,482 @@
+//! Finds problems in a quiz bank, such as empty answers, correct answers that
+//! are also incorrect ones and questions asked twice, and fixes those that
+//! need no one to decide how.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{
+    error::{QuizError, check_io},
+    i18n::{Message, Messages},
+    interface::Interface,
+    kind::{QuestionKind, empty_answer},
+    quiz::{Question, Quiz, load_quiz, normalize_answer, update_quiz},
+    store::QuizStore,
+};
+
+/// How alike two questions' texts must be, from 0 to 1, to be reported as
+/// duplicates.
+const SIMILAR_TEXT: f64 = 0.85;
+
+/// A problem with one of the questions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// The question's number, counting from 1.
+    pub number: usize,
+    pub message: Message,
+    /// Whether `--fix` can fix it.
+    pub fixable: bool,
+}
+
+impl Problem {
+    /// Puts the problem into words in the language of `messages`.
+    pub fn text(&self, messages: &Messages) -> String {
+        messages.text(
+            &Message::new("check-problem")
+                .with("number", self.number)
+                .with("problem", self.message.clone()),
+        )
+    }
+}
+
+/// Shows the problem in English.
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(&Messages::default()))
+    }
+}
+
+/// Checks the quiz in `quiz_file` and prints every problem found. With `fix`,
+/// first fixes the problems that can be fixed and saves the quiz.
+///
+/// Returns an error if problems remain, so that scripts can tell.
+pub fn check(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    fix: bool,
+) -> Result<(), QuizError> {
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    let mut problems = check_quiz(&quiz);
+    if fix && problems.iter().any(|p| p.fixable) {
+        let mut fixed = Vec::new();
+        update_quiz(store, quiz_file, |quiz| {
+            // Another session may have changed the quiz since it was checked.
+            fixed = fix_quiz(quiz);
+            problems = check_quiz(quiz);
+            Ok(())
+        })
+        .map_err(|source| QuizError::Save {
+            file: quiz_file.to_string(),
+            source,
+        })?;
+
+        for problem in &fixed {
+            let msg = inter.messages().format(
+                "check-fixed",
+                &[("problem", problem.text(inter.messages()).into())],
+            );
+            inter.write_stdout_line(&msg);
+        }
+    }
+
+    for problem in &problems {
+        let mut msg = problem.text(inter.messages());
+        if problem.fixable {
+            msg = inter
+                .messages()
+                .format("check-fixable", &[("problem", msg.into())]);
+        }
+        inter.write_stdout_line(&msg);
+    }
+    check_io(inter)?;
+
+    if problems.is_empty() {
+        let msg = inter
+            .messages()
+            .format("check-clean", &[("file", quiz_file.into())]);
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+    Err(QuizError::Problems {
+        file: quiz_file.to_string(),
+        count: problems.len(),
+        fixable: problems.iter().filter(|p| p.fixable).count(),
+    })
+}
+
+/// Warns that the quiz has problems, if it has any, without listing them.
+pub(crate) fn warn_problems(inter: &mut dyn Interface, quiz_file: &str, quiz: &Quiz) {
+    let count = check_quiz(quiz).len();
+    if count > 0 {
+        let msg = inter.messages().format(
+            "problems-warning",
+            &[("file", quiz_file.into()), ("count", count.into())],
+        );
+        inter.write_stdout_line(&format!("{}\n", msg));
+    }
+}
+
+/// Returns the problems of every question, and of questions that look like
+/// duplicates of earlier ones.
+pub fn check_quiz(quiz: &Quiz) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (idx, q) in quiz.questions.iter().enumerate() {
+        problems.extend(
+            check_question(q)
+                .into_iter()
+                .map(|(message, fixable)| Problem {
+                    number: idx + 1,
+                    message,
+                    fixable,
+                }),
+        );
+
+        if let Some(duplicate) = find_duplicate(&quiz.questions[..idx], q) {
+            problems.push(duplicate.problem(idx + 1));
+        }
+    }
+    problems.sort_by_key(|p| p.number);
+    problems
+}
+
+/// Returns the problems of a single question, each with whether it can be
+/// fixed.
+pub(crate) fn check_question(q: &Question) -> Vec<(Message, bool)> {
+    let mut problems = Vec::new();
+    if q.text.trim().is_empty() {
+        problems.push((Message::new("problem-empty-question"), false));
+    }
+
+    let mut kind = q.kind.clone();
+    problems.extend(fix_answers(&mut kind).into_iter().map(|m| (m, true)));
+    if let Some(problem) = kind.problem() {
+        problems.push((problem, false));
+    }
+    problems
+}
+
+/// Fixes the problems that can be fixed and returns them: empty and repeated
+/// answers and incorrect answers that are also correct are removed, and so
+/// are questions that are exactly the same as an earlier one.
+pub fn fix_quiz(quiz: &mut Quiz) -> Vec<Problem> {
+    let mut fixed = Vec::new();
+    for (idx, q) in quiz.questions.iter_mut().enumerate() {
+        fixed.extend(fix_answers(&mut q.kind).into_iter().map(|message| Problem {
+            number: idx + 1,
+            message,
+            fixable: true,
+        }));
+    }
+
+    let mut kept: Vec<Question> = Vec::with_capacity(quiz.questions.len());
+    for (idx, q) in quiz.questions.drain(..).enumerate() {
+        match find_duplicate(&kept, &q) {
+            Some(duplicate @ Duplicate::Same(_)) => fixed.push(duplicate.problem(idx + 1)),
+            _ => kept.push(q),
+        }
+    }
+    quiz.questions = kept;
+
+    fixed
+}
+
+/// Removes empty and repeated answers, and incorrect answers that are also
+/// correct, returning what was wrong.
+fn fix_answers(kind: &mut QuestionKind) -> Vec<Message> {
+    let mut fixed = Vec::new();
+    match kind {
+        QuestionKind::MultipleChoice {
+            correct,
+            incorrects,
+        } => {
+            let mut seen = BTreeSet::from([normalize_answer(correct)]);
+            remove_bad_answers(incorrects, "incorrect", &mut seen, &mut fixed);
+        }
+        QuestionKind::MultiSelect {
+            corrects,
+            incorrects,
+            ..
+        } => {
+            let mut seen = BTreeSet::new();
+            remove_bad_answers(corrects, "correct", &mut seen, &mut fixed);
+            remove_bad_answers(incorrects, "incorrect", &mut seen, &mut fixed);
+        }
+        QuestionKind::FreeText { accepted, .. } => {
+            remove_bad_answers(accepted, "accepted", &mut BTreeSet::new(), &mut fixed);
+        }
+        QuestionKind::Numeric { .. } | QuestionKind::Sealed { .. } => {}
+    }
+    fixed
+}
+
+/// Removes the empty `answers` and those already `seen`, which may be correct
+/// answers, adding the others to `seen`.
+fn remove_bad_answers(
+    answers: &mut Vec<String>,
+    what: &str,
+    seen: &mut BTreeSet<String>,
+    fixed: &mut Vec<Message>,
+) {
+    let correct = seen.clone();
+    answers.retain(|ans| {
+        let normalized = normalize_answer(ans);
+        if normalized.is_empty() {
+            fixed.push(empty_answer(what));
+            false
+        } else if what == "incorrect" && correct.contains(&normalized) {
+            fixed.push(Message::new("problem-also-correct").with("answer", ans.as_str()));
+            false
+        } else if !seen.insert(normalized) {
+            fixed.push(
+                Message::new("problem-repeated")
+                    .with("answers", what)
+                    .with("answer", ans.as_str()),
+            );
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// An earlier question, by index, that a question duplicates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Duplicate {
+    /// The same question with the same answers.
+    Same(usize),
+    /// A question with the same or much the same text.
+    Similar(usize),
+}
+
+impl Duplicate {
+    fn problem(self, number: usize) -> Problem {
+        match self {
+            Duplicate::Same(idx) => Problem {
+                number,
+                message: Message::new("problem-same").with("number", idx + 1),
+                fixable: true,
+            },
+            Duplicate::Similar(idx) => Problem {
+                number,
+                message: Message::new("problem-similar").with("number", idx + 1),
+                fixable: false,
+            },
+        }
+    }
+}
+
+/// Finds the first of `questions` that `q` duplicates, if any.
+pub(crate) fn find_duplicate(questions: &[Question], q: &Question) -> Option<Duplicate> {
+    let text = normalize_text(&q.text);
+    if text.is_empty() {
+        return None;
+    }
+
+    let same = questions
+        .iter()
+        .position(|other| other.kind == q.kind && normalize_text(&other.text) == text);
+    match same {
+        Some(idx) => Some(Duplicate::Same(idx)),
+        None => similar_question(questions, &q.text).map(Duplicate::Similar),
+    }
+}
+
+/// Finds the first of `questions` whose text is the same as or much like
+/// `text`, returning its index.
+pub(crate) fn similar_question(questions: &[Question], text: &str) -> Option<usize> {
+    let text = normalize_text(text);
+    if text.is_empty() {
+        return None;
+    }
+    questions
+        .iter()
+        .position(|other| similarity(&text, &normalize_text(&other.text)) >= SIMILAR_TEXT)
+}
+
+/// Lowercases `text` and keeps only its words, so that punctuation and
+/// spacing do not hide duplicates.
+fn normalize_text(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How alike two texts are, from 0 for nothing alike to 1 for the same,
+/// based on how many characters must change to turn one into the other.
+fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+/// The Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitution.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, QuizOptions, run_quiz};
+    use crate::schema::parse_quiz;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":2,"questions":[
+        {"text":"What is 2 + 2?","kind":"multiple_choice","correct":"4","incorrects":["3"," 4 ","3",""]},
+        {"text":"Capital of France?","kind":"free_text","accepted":["Paris","paris"]},
+        {"text":"what is 2+2","kind":"multiple_choice","correct":"4","incorrects":["3"]},
+        {"text":"Capitol of Frence?","kind":"free_text","accepted":["Paris"]},
+        {"text":" ","kind":"multiple_choice","correct":"","incorrects":["no"]}
+    ]}"#;
+
+    fn messages(problems: &[Problem]) -> Vec<String> {
+        problems.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_bad_answers_and_duplicates() {
+        let problems = check_quiz(&parse_quiz(QUIZ).unwrap());
+
+        assert_eq!(
+            messages(&problems),
+            [
+                "Question 1: the incorrect answer ' 4 ' is also a correct answer",
+                "Question 1: the incorrect answer '3' is repeated",
+                "Question 1: an incorrect answer is empty",
+                "Question 2: the accepted answer 'paris' is repeated",
+                "Question 3: looks like a duplicate of question 1",
+                "Question 4: looks like a duplicate of question 2",
+                "Question 5: the question is empty",
+                "Question 5: the correct answer is empty",
+            ]
+        );
+        let fixable: Vec<bool> = problems.iter().map(|p| p.fixable).collect();
+        assert_eq!(
+            fixable,
+            [true, true, true, true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn fix_removes_bad_answers_and_same_questions() {
+        let mut quiz = parse_quiz(QUIZ).unwrap();
+
+        let fixed = fix_quiz(&mut quiz);
+
+        assert_eq!(fixed.len(), 5);
+        assert_eq!(
+            fixed[4].to_string(),
+            "Question 3: is the same as question 1"
+        );
+        assert_eq!(quiz.len(), 4);
+        assert_eq!(
+            quiz.questions[0].kind,
+            QuestionKind::MultipleChoice {
+                correct: "4".to_string(),
+                incorrects: vec!["3".to_string()],
+            }
+        );
+        assert_eq!(
+            messages(&check_quiz(&quiz)),
+            [
+                "Question 3: looks like a duplicate of question 2",
+                "Question 4: the question is empty",
+                "Question 4: the correct answer is empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_command_fixes_and_reports_the_rest() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        let err = check(&mut inter, &mut store, DEFAULT_QUIZ_FILE, true).unwrap_err();
+
+        assert!(inter.stdout().starts_with(
+            "Fixed Question 1: the incorrect answer ' 4 ' is also a correct answer\n"
+        ));
+        assert!(inter.stdout().ends_with(
+            "\
+            Question 3: looks like a duplicate of question 2\n\
+            Question 4: the question is empty\n\
+            Question 4: the correct answer is empty\n\
+            "
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Found 3 problems in quiz.json, 0 of which --fix can fix."
+        );
+        let saved: serde_json::Value = serde_json::from_str(&store.quiz_file()).unwrap();
+        assert_eq!(saved["questions"][1]["accepted"], json!(["Paris"]));
+    }
+
+    #[test]
+    fn check_passes_clean_quiz_without_saving() {
+        let quiz = r#"{"version":2,"questions":[{"text":"True?","kind":"multiple_choice","correct":"yes","incorrects":["no"]}]}"#;
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+        check(&mut inter, &mut store, DEFAULT_QUIZ_FILE, true).unwrap();
+
+        assert_eq!(inter.stdout(), "No problems found in quiz.json.\n");
+        assert_eq!(store.quiz_file(), quiz);
+    }
+
+    #[test]
+    fn warn_problems() {
+        let quiz = r#"{"questions":[{"text":"Is Rust fun?","correct":"Yes","incorrects":[]}]}"#;
+        let mut inter = FakeInterface::with_stdin("a\n");
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Warning: quiz.json has 1 problems, see ./quizzer check\n\n\
+            Is Rust fun?\n a) Yes\n>>> Correct!\n\n\
+            You scored 1 out of 1 points.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn similarity_counts_edits() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(similarity("abc", "abc"), 1.0);
+        assert!(similarity("capital of france", "capital of spain") < SIMILAR_TEXT);
+    }
+}
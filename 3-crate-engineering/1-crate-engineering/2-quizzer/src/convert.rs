@@ -0,0 +1,964 @@
+use std::fmt;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::{
+    check::{Duplicate, check_question, find_duplicate},
+    error::{QuizError, check_io},
+    i18n::{Message, Messages},
+    interface::Interface,
+    kind::QuestionKind,
+    quiz::{Difficulty, Question, Quiz, load_quiz, parse_tags, update_quiz},
+    schema::parse_quiz_value,
+    store::QuizStore,
+};
+
+/// The formats questions can be imported from and exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// One question per row: question, tags, difficulty, explanation,
+    /// reference, correct answer, incorrect answers...
+    Csv,
+    /// A `questions` list, laid out like quiz.json
+    Yaml,
+    /// A heading per question followed by a `- [x]`/`- [ ]` answer list
+    Md,
+}
+
+impl Format {
+    /// Guesses the format of a file from its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "csv" => Some(Format::Csv),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "md" | "markdown" => Some(Format::Md),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with one of the questions being imported.
+#[derive(Debug, PartialEq)]
+pub struct RowError {
+    /// The line the problem was found on, if it is known.
+    pub line: Option<usize>,
+    pub message: Message,
+}
+
+impl RowError {
+    fn new(line: Option<usize>, message: Message) -> Self {
+        Self { line, message }
+    }
+
+    /// A problem reported by the parser of the format, whose reason is kept
+    /// as it is.
+    fn parse(line: Option<usize>, error: impl fmt::Display) -> Self {
+        Self::new(
+            line,
+            Message::new("import-parse-error").with("error", error.to_string()),
+        )
+    }
+
+    /// Puts the problem into words in the language of `messages`.
+    pub fn text(&self, messages: &Messages) -> String {
+        match self.line {
+            Some(line) => messages.text(
+                &Message::new("import-line")
+                    .with("line", line)
+                    .with("problem", self.message.clone()),
+            ),
+            None => messages.text(&self.message),
+        }
+    }
+}
+
+/// Shows the problem in English.
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(&Messages::default()))
+    }
+}
+
+/// Imports the questions in `path` and appends them to the quiz in `quiz_file`.
+/// Nothing is imported if any of the questions is invalid.
+pub fn import(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    path: &str,
+    format: Option<Format>,
+) -> Result<(), QuizError> {
+    let format = format
+        .or_else(|| Format::from_path(path))
+        .ok_or_else(|| QuizError::UnknownFormat(path.to_string()))?;
+
+    let contents = store.read_file(path).map_err(|source| QuizError::Load {
+        file: path.to_string(),
+        source,
+    })?;
+
+    let new_questions = parse_questions(format, &contents).map_err(|errors| QuizError::Import {
+        file: path.to_string(),
+        errors,
+    })?;
+
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    let mut errors = Vec::new();
+    for q in &new_questions {
+        match find_duplicate(&quiz.questions, q) {
+            Some(Duplicate::Same(idx)) => errors.push(RowError::new(
+                None,
+                Message::new("import-already")
+                    .with("question", q.text.as_str())
+                    .with("number", idx + 1),
+            )),
+            Some(Duplicate::Similar(idx)) => {
+                let msg = inter.messages().format(
+                    "import-similar",
+                    &[
+                        ("question", q.text.as_str().into()),
+                        ("number", (idx + 1).into()),
+                        ("text", quiz.questions[idx].text.as_str().into()),
+                    ],
+                );
+                inter.write_stdout_line(&msg);
+            }
+            None => {}
+        }
+    }
+    if !errors.is_empty() {
+        return Err(QuizError::Import {
+            file: path.to_string(),
+            errors,
+        });
+    }
+
+    let count = new_questions.len();
+    update_quiz(store, quiz_file, |quiz| {
+        quiz.append_questions(new_questions.clone())
+    })
+    .map_err(|source| QuizError::Save {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    let msg = inter
+        .messages()
+        .format("import-done", &[("count", count.into())]);
+    inter.write_stdout_line(&msg);
+    check_io(inter)
+}
+
+/// Writes the quiz in `quiz_file` to stdout in the given format.
+pub fn export(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    format: Format,
+) -> Result<(), QuizError> {
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    let text = format_questions(format, &quiz).map_err(|source| QuizError::Export {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    inter.write_stdout(&text);
+    check_io(inter)
+}
+
+/// Parses questions written in `format`, collecting every invalid question.
+pub fn parse_questions(format: Format, text: &str) -> Result<Vec<Question>, Vec<RowError>> {
+    match format {
+        Format::Csv => parse_csv(text),
+        Format::Yaml => parse_yaml(text),
+        Format::Md => parse_md(text),
+    }
+}
+
+/// Formats the questions of `quiz` in `format`.
+pub fn format_questions(format: Format, quiz: &Quiz) -> anyhow::Result<String> {
+    match format {
+        Format::Csv => format_csv(quiz),
+        Format::Yaml => Ok(serde_yaml::to_string(quiz)?),
+        Format::Md => format_md(quiz),
+    }
+}
+
+/// Collects the questions if they are all valid and none is repeated,
+/// otherwise the errors.
+fn collect_valid(
+    parsed: Vec<(Option<usize>, Question)>,
+    mut errors: Vec<RowError>,
+) -> Result<Vec<Question>, Vec<RowError>> {
+    let mut questions = Vec::with_capacity(parsed.len());
+    let mut lines = Vec::with_capacity(parsed.len());
+    for (line, q) in parsed {
+        let problems = check_question(&q);
+        if !problems.is_empty() {
+            errors.extend(problems.into_iter().map(|(p, _)| RowError::new(line, p)));
+        } else if let Some(Duplicate::Same(idx)) = find_duplicate(&questions, &q) {
+            let message = match lines[idx] {
+                Some(earlier) => Message::new("import-same-line").with("line", earlier),
+                None => Message::new("import-same-question").with("number", idx + 1),
+            };
+            errors.push(RowError::new(line, message));
+        } else {
+            questions.push(q);
+            lines.push(line);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(questions)
+    } else {
+        errors.sort_by_key(|e| e.line);
+        Err(errors)
+    }
+}
+
+/// Parses the difficulty of a question, or returns why it cannot be.
+fn parse_difficulty(value: &str) -> Result<Difficulty, Message> {
+    value
+        .parse()
+        .map_err(|_| Message::new("import-unknown-difficulty").with("difficulty", value.trim()))
+}
+
+/// The columns of a CSV file between the question and its answers, which
+/// files written before they were added go without.
+const CSV_DETAILS: [&str; 4] = ["tags", "difficulty", "explanation", "reference"];
+
+/// Reads a CSV file with a header row, followed by one row per question:
+/// the question, its details if the header names them, its correct answer
+/// and then its incorrect answers.
+fn parse_csv(text: &str) -> Result<Vec<Question>, Vec<RowError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+
+    let has_details = match reader.headers() {
+        Ok(headers) => headers
+            .iter()
+            .skip(1)
+            .take(CSV_DETAILS.len())
+            .map(|h| h.trim().to_lowercase())
+            .eq(CSV_DETAILS),
+        Err(e) => return Err(vec![RowError::parse(Some(1), e)]),
+    };
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                let line = e.position().map(|p| p.line() as usize);
+                errors.push(RowError::parse(line, e));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line() as usize);
+
+        let mut fields = record.iter().map(str::trim);
+        let text = fields.next().unwrap_or_default().to_string();
+        let mut question = Question::default();
+        if has_details {
+            let mut details = fields.by_ref().take(CSV_DETAILS.len());
+            let mut next = || details.next().filter(|f| !f.is_empty());
+            question.tags = next().map(parse_tags).unwrap_or_default();
+            question.difficulty = match next().map(parse_difficulty).transpose() {
+                Ok(difficulty) => difficulty,
+                Err(message) => {
+                    errors.push(RowError::new(line, message));
+                    continue;
+                }
+            };
+            question.explanation = next().map(String::from);
+            question.reference = next().map(String::from);
+        }
+        let correct = fields.next().unwrap_or_default().to_string();
+        // Rows are padded with empty cells by spreadsheets.
+        let incorrects = fields.filter(|f| !f.is_empty()).map(String::from).collect();
+
+        parsed.push((
+            line,
+            Question {
+                text,
+                kind: QuestionKind::MultipleChoice {
+                    correct,
+                    incorrects,
+                },
+                ..question
+            },
+        ));
+    }
+
+    collect_valid(parsed, errors)
+}
+
+fn format_csv(quiz: &Quiz) -> anyhow::Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(Vec::new());
+
+    let mut header = vec!["question"];
+    header.extend(CSV_DETAILS);
+    header.extend(["correct", "incorrects"]);
+    writer.write_record(header)?;
+    for (idx, q) in quiz.questions.iter().enumerate() {
+        let QuestionKind::MultipleChoice {
+            correct,
+            incorrects,
+        } = &q.kind
+        else {
+            return Err(only_multiple_choice(idx, q, Format::Csv));
+        };
+        let tags = q.tags.join(", ");
+        let difficulty = q.difficulty.map(|d| d.to_string()).unwrap_or_default();
+        let mut record = vec![
+            q.text.as_str(),
+            &tags,
+            &difficulty,
+            q.explanation.as_deref().unwrap_or_default(),
+            q.reference.as_deref().unwrap_or_default(),
+            correct.as_str(),
+        ];
+        record.extend(incorrects.iter().map(String::as_str));
+        writer.write_record(record)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// The CSV and Markdown formats only have room for a single correct answer.
+fn only_multiple_choice(idx: usize, q: &Question, format: Format) -> anyhow::Error {
+    if q.kind.is_sealed() {
+        return anyhow::anyhow!(
+            "question {} is sealed, so its correct answers are not known",
+            idx + 1
+        );
+    }
+    anyhow::anyhow!(
+        "question {} is a {} question, which {:?} cannot hold; export to YAML instead",
+        idx + 1,
+        q.kind.kind(),
+        format
+    )
+}
+
+fn parse_yaml(text: &str) -> Result<Vec<Question>, Vec<RowError>> {
+    let doc: serde_json::Value = match serde_yaml::from_str(text) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let line = e.location().map(|l| l.line());
+            return Err(vec![RowError::parse(line, e)]);
+        }
+    };
+    // Questions without a kind, as written before kinds were added, are
+    // upgraded the same way as old quiz files.
+    let quiz = match parse_quiz_value(doc) {
+        Ok(q) => q,
+        Err(e) => return Err(vec![RowError::parse(None, e)]),
+    };
+
+    // serde_yaml does not keep track of where values came from, so look up
+    // the lines of the question list items to point errors at.
+    let item_lines = yaml_item_lines(text);
+    let lines: Vec<Option<usize>> = if item_lines.len() == quiz.questions.len() {
+        item_lines.into_iter().map(Some).collect()
+    } else {
+        vec![None; quiz.questions.len()]
+    };
+
+    let parsed = lines.into_iter().zip(quiz.questions).collect();
+    collect_valid(parsed, Vec::new())
+}
+
+/// Returns the line numbers of the least indented `- ` list items, which in
+/// a block style quiz are the questions.
+fn yaml_item_lines(text: &str) -> Vec<usize> {
+    let items: Vec<(usize, usize)> = text
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let trimmed = line.trim_start();
+            (trimmed.starts_with("- ") || trimmed == "-")
+                .then(|| (line.len() - trimmed.len(), idx + 1))
+        })
+        .collect();
+
+    let Some(min_indent) = items.iter().map(|(indent, _)| *indent).min() else {
+        return Vec::new();
+    };
+
+    items
+        .into_iter()
+        .filter(|(indent, _)| *indent == min_indent)
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// Reads questions written as a Markdown heading followed by a task list in
+/// which the correct answer is checked:
+///
+/// ```markdown
+/// ## What is 2 + 2?
+///
+/// - [x] 4
+/// - [ ] 3
+/// - [ ] 5
+/// ```
+///
+/// A question can also have `Tags: a, b`, `Difficulty: easy`,
+/// `Explanation: ...` and `Reference: ...` lines. Headings without answers,
+/// such as the document's title, are not questions and are skipped.
+fn parse_md(text: &str) -> Result<Vec<Question>, Vec<RowError>> {
+    // The line of each question, the question and every correct and incorrect
+    // answer given.
+    let mut parsed: Vec<(usize, Question, Vec<String>, Vec<String>)> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+
+        if line.starts_with('#') {
+            let q_text = line.trim_start_matches('#').trim().to_string();
+            let question = Question {
+                text: q_text,
+                ..Default::default()
+            };
+            parsed.push((line_no, question, Vec::new(), Vec::new()));
+        } else if let Some((key, value)) = md_field(line) {
+            let Some((_, question, _, _)) = parsed.last_mut() else {
+                errors.push(RowError::new(
+                    Some(line_no),
+                    Message::new("import-field-first").with("field", key),
+                ));
+                continue;
+            };
+            match key {
+                "tags" => question.tags = parse_tags(value),
+                "explanation" => question.explanation = Some(value.to_string()),
+                "reference" => question.reference = Some(value.to_string()),
+                _ => match parse_difficulty(value) {
+                    Ok(difficulty) => question.difficulty = Some(difficulty),
+                    Err(message) => errors.push(RowError::new(Some(line_no), message)),
+                },
+            }
+        } else if let Some((checked, ans)) = md_answer(line) {
+            let Some((_, _, corrects, incorrects)) = parsed.last_mut() else {
+                errors.push(RowError::new(
+                    Some(line_no),
+                    Message::new("import-answer-first"),
+                ));
+                continue;
+            };
+            if checked {
+                corrects.push(ans.to_string());
+            } else {
+                incorrects.push(ans.to_string());
+            }
+        } else if !line.is_empty() {
+            errors.push(RowError::new(
+                Some(line_no),
+                Message::new("import-unexpected-line"),
+            ));
+        }
+    }
+
+    let mut questions = Vec::with_capacity(parsed.len());
+    for (line_no, mut question, corrects, incorrects) in parsed {
+        if corrects.is_empty() && incorrects.is_empty() {
+            continue;
+        }
+        match corrects.len() {
+            0 => errors.push(RowError::new(
+                Some(line_no),
+                Message::new("import-none-checked"),
+            )),
+            1 => {
+                question.kind = QuestionKind::MultipleChoice {
+                    correct: corrects.into_iter().next().unwrap_or_default(),
+                    incorrects,
+                };
+                questions.push((Some(line_no), question));
+            }
+            _ => errors.push(RowError::new(
+                Some(line_no),
+                Message::new("import-several-checked"),
+            )),
+        }
+    }
+
+    collect_valid(questions, errors)
+}
+
+/// Splits a `- [x] answer` or `- [ ] answer` line into whether it is checked
+/// and the answer.
+fn md_answer(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))?
+        .trim_start();
+    let (checked, ans) = if let Some(ans) = rest.strip_prefix("[ ]") {
+        (false, ans)
+    } else if let Some(ans) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, ans)
+    } else {
+        return None;
+    };
+    Some((checked, ans.trim()))
+}
+
+/// Splits a `Tags: ...`, `Difficulty: ...`, `Explanation: ...` or
+/// `Reference: ...` line into its lowercase key and value.
+fn md_field(line: &str) -> Option<(&'static str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    match key.trim().to_lowercase().as_str() {
+        "tags" => Some(("tags", value.trim())),
+        "difficulty" => Some(("difficulty", value.trim())),
+        "explanation" => Some(("explanation", value.trim())),
+        "reference" => Some(("reference", value.trim())),
+        _ => None,
+    }
+}
+
+fn format_md(quiz: &Quiz) -> anyhow::Result<String> {
+    let mut output = String::new();
+    for (idx, q) in quiz.questions.iter().enumerate() {
+        let QuestionKind::MultipleChoice {
+            correct,
+            incorrects,
+        } = &q.kind
+        else {
+            return Err(only_multiple_choice(idx, q, Format::Md));
+        };
+
+        if idx > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("## {}\n\n", q.text));
+        if !q.tags.is_empty() {
+            output.push_str(&format!("Tags: {}\n", q.tags.join(", ")));
+        }
+        if let Some(difficulty) = q.difficulty {
+            output.push_str(&format!("Difficulty: {}\n", difficulty));
+        }
+        if let Some(explanation) = &q.explanation {
+            output.push_str(&format!("Explanation: {}\n", explanation));
+        }
+        if let Some(reference) = &q.reference {
+            output.push_str(&format!("Reference: {}\n", reference));
+        }
+        if !q.tags.is_empty()
+            || q.difficulty.is_some()
+            || q.explanation.is_some()
+            || q.reference.is_some()
+        {
+            output.push('\n');
+        }
+        output.push_str(&format!("- [x] {}\n", correct));
+        for ans in incorrects {
+            output.push_str(&format!("- [ ] {}\n", ans));
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_interface::FakeInterface;
+    use crate::i18n::Messages;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, Difficulty};
+
+    use super::*;
+
+    fn sample_quiz() -> Quiz {
+        Quiz {
+            questions: vec![
+                Question {
+                    text: "What is 2 + 2?".to_string(),
+                    kind: QuestionKind::MultipleChoice {
+                        correct: "4".to_string(),
+                        incorrects: vec!["3".to_string(), "5".to_string()],
+                    },
+                    tags: vec!["maths".to_string(), "easy sums".to_string()],
+                    difficulty: Some(Difficulty::Easy),
+                    explanation: Some("Two and two make four.".to_string()),
+                    reference: Some("Chapter 1".to_string()),
+                },
+                Question {
+                    text: "Is Rust memory safe, in general?".to_string(),
+                    kind: QuestionKind::MultipleChoice {
+                        correct: "yes".to_string(),
+                        incorrects: vec!["no".to_string()],
+                    },
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    fn texts(questions: &[Question]) -> Vec<(&str, &str, Vec<&str>)> {
+        questions
+            .iter()
+            .map(|q| match &q.kind {
+                QuestionKind::MultipleChoice {
+                    correct,
+                    incorrects,
+                } => (
+                    q.text.as_str(),
+                    correct.as_str(),
+                    incorrects.iter().map(String::as_str).collect(),
+                ),
+                other => panic!("unexpected {} question", other.kind()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        let quiz = sample_quiz();
+
+        for format in [Format::Csv, Format::Yaml, Format::Md] {
+            let text = format_questions(format, &quiz).unwrap();
+            let questions = parse_questions(format, &text).unwrap();
+
+            assert_eq!(texts(&questions), texts(&quiz.questions), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn formats_keep_tags_difficulty_and_notes() {
+        let quiz = sample_quiz();
+
+        for format in [Format::Csv, Format::Yaml, Format::Md] {
+            let text = format_questions(format, &quiz).unwrap();
+            let questions = parse_questions(format, &text).unwrap();
+
+            assert_eq!(
+                questions[0].tags,
+                vec!["maths", "easy sums"],
+                "{:?}",
+                format
+            );
+            assert_eq!(
+                questions[0].difficulty,
+                Some(Difficulty::Easy),
+                "{:?}",
+                format
+            );
+            assert_eq!(
+                questions[0].notes(&Messages::default()).as_deref(),
+                Some("Explanation: Two and two make four.\nReference: Chapter 1"),
+                "{:?}",
+                format
+            );
+            assert!(questions[1].tags.is_empty(), "{:?}", format);
+            assert_eq!(questions[1].difficulty, None, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn md_skips_headings_without_answers() {
+        let text = "# Maths quiz\n\n## What is 2 + 2?\n- [x] 4\n- [ ] 3\n\n# Logic\n## True?\n- [x] yes\n- [ ] no\n";
+
+        let questions = parse_questions(Format::Md, text).unwrap();
+
+        assert_eq!(
+            questions
+                .iter()
+                .map(|q| q.text.as_str())
+                .collect::<Vec<_>>(),
+            ["What is 2 + 2?", "True?"]
+        );
+    }
+
+    #[test]
+    fn md_reports_unknown_difficulty() {
+        let text = "## True?\nDifficulty: tricky\n- [x] yes\n- [ ] no\n";
+
+        let errors = parse_questions(Format::Md, text).unwrap_err();
+
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2: unknown difficulty 'tricky', expected easy, medium or hard"
+        );
+    }
+
+    #[test]
+    fn csv_has_columns_for_details() {
+        let text = format_questions(Format::Csv, &sample_quiz()).unwrap();
+
+        assert_eq!(
+            text,
+            "\
+            question,tags,difficulty,explanation,reference,correct,incorrects\n\
+            What is 2 + 2?,\"maths, easy sums\",easy,Two and two make four.,Chapter 1,4,3,5\n\
+            \"Is Rust memory safe, in general?\",,,,,yes,no\n\
+            "
+        );
+    }
+
+    #[test]
+    fn csv_reports_unknown_difficulty() {
+        let text = "question,tags,difficulty,explanation,reference,correct,incorrects\n\
+                    True?,,tricky,,,yes,no\n";
+
+        let errors = parse_questions(Format::Csv, text).unwrap_err();
+
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2: unknown difficulty 'tricky', expected easy, medium or hard"
+        );
+    }
+
+    #[test]
+    fn imported_questions_are_checked_like_added_ones() {
+        let text = "question,correct,incorrects\n\
+                    True?,yes,Yes,no\n\
+                    2 + 2?,4,3,3\n\
+                    False?,no,yes\n\
+                    False?,no,yes\n";
+
+        let errors = parse_questions(Format::Csv, text).unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(RowError::to_string).collect::<Vec<_>>(),
+            [
+                "line 2: the incorrect answer 'Yes' is also a correct answer",
+                "line 3: the incorrect answer '3' is repeated",
+                "line 5: the same question as on line 4"
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_ignores_padding_cells() {
+        let text = "question,correct,incorrects,,\nWhat is 2 + 2?,4,3,5,\nTrue?,yes,no,,\n";
+
+        let questions = parse_questions(Format::Csv, text).unwrap();
+
+        assert_eq!(
+            texts(&questions),
+            vec![
+                ("What is 2 + 2?", "4", vec!["3", "5"]),
+                ("True?", "yes", vec!["no"])
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_reports_invalid_rows_by_line() {
+        let text = "question,correct,incorrects\nWhat is 2 + 2?,4,3\n,4,3\nTrue?,yes\n";
+
+        let errors = parse_questions(Format::Csv, text).unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 3: the question is empty",
+                "line 4: at least one incorrect answer is required"
+            ]
+        );
+    }
+
+    #[test]
+    fn yaml_reports_invalid_questions_by_line() {
+        let text = "\
+questions:
+- text: What is 2 + 2?
+  correct: '4'
+  incorrects:
+  - '3'
+- text: True?
+  correct: ''
+  incorrects:
+  - 'no'
+";
+
+        let errors = parse_questions(Format::Yaml, text).unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![RowError::new(
+                Some(6),
+                Message::new("problem-empty-correct")
+            )]
+        );
+    }
+
+    #[test]
+    fn md_reports_invalid_questions_by_line() {
+        let text = "\
+- [ ] stray answer
+
+## What is 2 + 2?
+
+- [x] 4
+- [x] four
+- [ ] 3
+
+## True?
+- [ ] no
+";
+
+        let errors = parse_questions(Format::Md, text).unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 1: answer given before any question",
+                "line 3: more than one answer is checked",
+                "line 9: no answer is checked"
+            ]
+        );
+    }
+
+    #[test]
+    fn import_appends_to_quiz() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new()
+            .with_quiz_file(
+                r#"{"questions":[{"text":"What's my name?","correct":"quizzer","incorrects":["andy"]}]}"#,
+            )
+            .with_file("new.csv", "question,correct,incorrects\nTrue?,yes,no\n");
+
+        import(&mut inter, &mut store, DEFAULT_QUIZ_FILE, "new.csv", None).unwrap();
+
+        assert_eq!(inter.stdout(), "Imported 1 questions.\n");
+        let quiz: Quiz = serde_json::from_str(&store.quiz_file()).unwrap();
+        assert_eq!(
+            texts(&quiz.questions),
+            vec![
+                ("What's my name?", "quizzer", vec!["andy"]),
+                ("True?", "yes", vec!["no"])
+            ]
+        );
+    }
+
+    #[test]
+    fn import_reports_errors_without_saving() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_file(
+            "new.md",
+            "## True?\n- [x] yes\n- [ ] no\n\n## False?\n- [ ] yes\n",
+        );
+
+        let err = import(&mut inter, &mut store, DEFAULT_QUIZ_FILE, "new.md", None).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to import 'new.md':\n  line 5: no answer is checked"
+        );
+        assert_eq!(inter.stdout(), "");
+        assert!(store.saved_quiz_file().is_none());
+    }
+
+    #[test]
+    fn import_fails_for_unknown_format_or_missing_file() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        let err = import(&mut inter, &mut store, DEFAULT_QUIZ_FILE, "new.txt", None).unwrap_err();
+        assert!(matches!(err, QuizError::UnknownFormat(_)));
+
+        let err = import(&mut inter, &mut store, DEFAULT_QUIZ_FILE, "new.csv", None).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to load new.csv: No such file");
+        assert!(store.saved_quiz_file().is_none());
+    }
+
+    #[test]
+    fn import_refuses_questions_already_in_quiz() {
+        let mut inter = FakeInterface::new();
+        let quiz = r#"{"questions":[{"text":"What's my name?","correct":"quizzer","incorrects":["andy"]},
+                                    {"text":"True?","correct":"yes","incorrects":["no"]}]}"#;
+        let mut store = MemoryStore::new().with_quiz_file(quiz).with_file(
+            "new.csv",
+            "question,correct,incorrects\nTrue?,yes,no\nWhat is my name?,bob,andy\n",
+        );
+
+        let err = import(&mut inter, &mut store, DEFAULT_QUIZ_FILE, "new.csv", None).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to import 'new.csv':\n  'True?' is already question 2 of the quiz"
+        );
+        assert_eq!(
+            inter.stdout(),
+            "Warning: 'What is my name?' looks like question 1: What's my name?\n"
+        );
+        assert_eq!(store.quiz_file(), quiz);
+    }
+
+    #[test]
+    fn export_writes_to_stdout() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[{"text":"True?","correct":"yes","incorrects":["no"]}]}"#,
+        );
+
+        export(&mut inter, &mut store, DEFAULT_QUIZ_FILE, Format::Md).unwrap();
+
+        assert_eq!(inter.stdout(), "## True?\n\n- [x] yes\n- [ ] no\n");
+    }
+
+    #[test]
+    fn yaml_keeps_question_kinds_and_upgrades_old_questions() {
+        let mut quiz = sample_quiz();
+        quiz.questions.push(Question {
+            text: "What is 1/3?".to_string(),
+            kind: QuestionKind::Numeric {
+                answer: 0.33,
+                tolerance: 0.01,
+            },
+            ..Default::default()
+        });
+
+        let text = format_questions(Format::Yaml, &quiz).unwrap();
+        let questions = parse_questions(Format::Yaml, &text).unwrap();
+        assert_eq!(questions[2].kind, quiz.questions[2].kind);
+
+        let old = "questions:\n- text: True?\n  correct: 'yes'\n  incorrects:\n  - 'no'\n";
+        let questions = parse_questions(Format::Yaml, old).unwrap();
+        assert_eq!(texts(&questions), vec![("True?", "yes", vec!["no"])]);
+    }
+
+    #[test]
+    fn csv_and_md_only_export_multiple_choice() {
+        let quiz = Quiz {
+            questions: vec![Question {
+                text: "Capital of France?".to_string(),
+                kind: QuestionKind::FreeText {
+                    accepted: vec!["Paris".to_string()],
+                    pattern: None,
+                },
+                ..Default::default()
+            }],
+        };
+
+        for format in [Format::Csv, Format::Md] {
+            let err = format_questions(format, &quiz).unwrap_err();
+
+            assert!(
+                err.to_string()
+                    .starts_with("question 1 is a free-text question"),
+                "{:?}",
+                format
+            );
+        }
+    }
+}
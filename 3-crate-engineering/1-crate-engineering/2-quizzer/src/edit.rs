@@ -0,0 +1,711 @@
+use anyhow::anyhow;
+
+use crate::{
+    add::AnswerArgs,
+    check::check_question,
+    error::{QuizError, check_io},
+    i18n::{Arg, Message},
+    interface::Interface,
+    kind::QuestionKind,
+    quiz::{Difficulty, Question, Quiz, load_quiz, parse_tags, update_quiz},
+    store::QuizStore,
+};
+
+/// Interactively edits question `number` (counting from 1) of the quiz in
+/// `quiz_file`. Every prompt shows the current value, which is kept if the
+/// answer is left blank.
+///
+/// Study logs know questions by their text, so changing the text starts the
+/// question's study history afresh.
+pub fn edit_question(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    number: usize,
+) -> Result<(), QuizError> {
+    let quiz = load_for_change(store, quiz_file, number)?;
+    let current = &quiz.questions[number - 1];
+
+    say(inter, Message::new("edit-start").with("number", number));
+
+    say(
+        inter,
+        Message::new("edit-text").with("current", current.text.as_str()),
+    );
+    let Some(text) = read_or_keep(inter, &current.text) else {
+        return check_io(inter);
+    };
+
+    let kind = match edit_answers(inter, &current.kind) {
+        Some(kind) => kind?,
+        None => return check_io(inter),
+    };
+
+    say(
+        inter,
+        Message::new("edit-tags").with("current", or_none(&current.tags.join(", "))),
+    );
+    let Some(tags) = inter.read_stdin_line() else {
+        return check_io(inter);
+    };
+    let tags = match tags.trim() {
+        "" => current.tags.clone(),
+        "-" => Vec::new(),
+        tags => parse_tags(tags),
+    };
+
+    let Some(difficulty) = read_difficulty(inter, current.difficulty) else {
+        return check_io(inter);
+    };
+
+    let Some(explanation) = read_note(inter, "edit-explanation", &current.explanation) else {
+        return check_io(inter);
+    };
+    let Some(reference) = read_note(inter, "edit-reference", &current.reference) else {
+        return check_io(inter);
+    };
+
+    let edited = Question {
+        text,
+        kind,
+        tags,
+        difficulty,
+        explanation,
+        reference,
+    };
+    if let Some((problem, _)) = check_question(&edited).into_iter().next() {
+        return Err(QuizError::InvalidQuestion(
+            Message::new("edit-invalid")
+                .with("number", number)
+                .with("problem", problem),
+        ));
+    }
+
+    update_quiz(store, quiz_file, |quiz| {
+        check_unchanged(quiz, number, current)?;
+        quiz.questions[number - 1] = edited.clone();
+        Ok(())
+    })
+    .map_err(|source| QuizError::Save {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    say(inter, Message::new("edit-updated").with("number", number));
+    check_io(inter)
+}
+
+/// Asks for the new answers of a question, each prompt showing the current
+/// ones, and builds them into answers the way `quizzer add` does. The answers
+/// of sealed questions are kept. Returns `None` if there is no more input.
+fn edit_answers(
+    inter: &mut dyn Interface,
+    kind: &QuestionKind,
+) -> Option<Result<QuestionKind, QuizError>> {
+    let mut correct = None;
+    let mut also_correct = Vec::new();
+    let mut incorrects = Vec::new();
+    let mut partial = false;
+    let mut pattern = None;
+    let mut tolerance = None;
+    match kind {
+        QuestionKind::MultipleChoice {
+            correct: current,
+            incorrects: current_incorrects,
+        } => {
+            say(
+                inter,
+                Message::new("edit-correct").with("current", current.as_str()),
+            );
+            correct = Some(read_or_keep(inter, current)?);
+            incorrects = edit_list(inter, current_incorrects, &INCORRECTS)?;
+        }
+        QuestionKind::MultiSelect {
+            corrects,
+            incorrects: current_incorrects,
+            partial: current_partial,
+        } => {
+            also_correct = edit_list(inter, corrects, &CORRECTS)?;
+            correct = Some(also_correct.remove(0));
+            incorrects = edit_list(inter, current_incorrects, &INCORRECTS)?;
+            partial = read_partial(inter, *current_partial)?;
+        }
+        QuestionKind::FreeText {
+            accepted,
+            pattern: current_pattern,
+        } => {
+            also_correct = edit_list(inter, accepted, &ACCEPTED)?;
+            if !also_correct.is_empty() {
+                correct = Some(also_correct.remove(0));
+            }
+            pattern = read_note(inter, "edit-pattern", current_pattern)?;
+        }
+        QuestionKind::Numeric {
+            answer,
+            tolerance: current_tolerance,
+        } => {
+            correct = Some(read_number(inter, *answer)?);
+            tolerance = Some(read_tolerance(inter, *current_tolerance)?);
+        }
+        QuestionKind::Sealed { .. } => {
+            say(inter, Message::new("edit-sealed"));
+            return Some(Ok(kind.clone()));
+        }
+    }
+
+    let answers = AnswerArgs {
+        kind: kind.kind(),
+        correct: correct.as_deref(),
+        incorrects: &incorrects,
+        also_correct: &also_correct,
+        partial,
+        pattern: pattern.as_deref(),
+        tolerance,
+        ..Default::default()
+    };
+    Some(answers.to_question_kind())
+}
+
+/// The prompts for editing a list of answers.
+struct ListPrompts {
+    /// Asks for a new value of one of the answers, given its number.
+    edit: &'static str,
+    /// Asks for one more answer.
+    add: &'static str,
+    /// Says that at least one answer is needed, if one is.
+    required: Option<&'static str>,
+}
+
+const CORRECTS: ListPrompts = ListPrompts {
+    edit: "edit-correct-n",
+    add: "edit-add-correct",
+    required: Some("add-corrects-required"),
+};
+
+const INCORRECTS: ListPrompts = ListPrompts {
+    edit: "edit-incorrect",
+    add: "edit-add-incorrect",
+    required: Some("add-incorrects-required"),
+};
+
+const ACCEPTED: ListPrompts = ListPrompts {
+    edit: "edit-accepted",
+    add: "edit-add-accepted",
+    required: None,
+};
+
+/// Asks for a new value of each of the `current` answers, where '-' removes
+/// one, then for more answers until a blank line is entered.
+/// Returns `None` if there is no more input.
+fn edit_list(
+    inter: &mut dyn Interface,
+    current: &[String],
+    prompts: &ListPrompts,
+) -> Option<Vec<String>> {
+    let mut answers = Vec::with_capacity(current.len());
+    for (idx, ans) in current.iter().enumerate() {
+        say(
+            inter,
+            Message::new(prompts.edit)
+                .with("number", idx + 1)
+                .with("current", ans.as_str()),
+        );
+        let new_ans = read_or_keep(inter, ans)?;
+        if new_ans.trim() != "-" {
+            answers.push(new_ans);
+        }
+    }
+
+    loop {
+        say(inter, Message::new(prompts.add));
+        let ans = inter.read_stdin_line()?;
+
+        if !ans.trim().is_empty() {
+            answers.push(ans);
+        } else if let Some(required) = prompts.required.filter(|_| answers.is_empty()) {
+            say(inter, Message::new(required));
+        } else {
+            return Some(answers);
+        }
+    }
+}
+
+/// Asks whether a multi-select question gives partial credit, keeping
+/// `current` if the answer is blank. Returns `None` if there is no more input.
+fn read_partial(inter: &mut dyn Interface, current: bool) -> Option<bool> {
+    let shown = Message::new(if current { "yes" } else { "no" });
+    say(inter, Message::new("edit-partial").with("current", shown));
+    let ans = inter.read_stdin_line()?;
+    if ans.trim().is_empty() {
+        Some(current)
+    } else {
+        Some(inter.messages().is_yes(&ans))
+    }
+}
+
+/// Asks for the answer of a numeric question until a number is given,
+/// keeping `current` if the answer is blank. Returns `None` if there is no
+/// more input.
+fn read_number(inter: &mut dyn Interface, current: f64) -> Option<String> {
+    loop {
+        say(
+            inter,
+            Message::new("edit-number").with("current", current.to_string()),
+        );
+        let ans = inter.read_stdin_line()?;
+        match ans.trim() {
+            "" => return Some(current.to_string()),
+            ans if ans.parse::<f64>().is_ok_and(f64::is_finite) => return Some(ans.to_string()),
+            ans => say(inter, Message::new("add-not-a-number").with("answer", ans)),
+        }
+    }
+}
+
+/// Asks how far off the answer to a numeric question can be until a number of
+/// zero or more is given, keeping `current` if the answer is blank.
+/// Returns `None` if there is no more input.
+fn read_tolerance(inter: &mut dyn Interface, current: f64) -> Option<f64> {
+    loop {
+        say(
+            inter,
+            Message::new("edit-tolerance").with("current", current.to_string()),
+        );
+        let ans = inter.read_stdin_line()?;
+        if ans.trim().is_empty() {
+            return Some(current);
+        }
+        match ans.trim().parse::<f64>() {
+            Ok(n) if n >= 0.0 => return Some(n),
+            _ => say(
+                inter,
+                Message::new("add-bad-tolerance").with("answer", ans.trim()),
+            ),
+        }
+    }
+}
+
+/// Removes question `number` (counting from 1) from the quiz in `quiz_file`.
+pub fn remove_question(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    number: usize,
+) -> Result<(), QuizError> {
+    let quiz = load_for_change(store, quiz_file, number)?;
+
+    let removed = &quiz.questions[number - 1];
+    update_quiz(store, quiz_file, |quiz| {
+        check_unchanged(quiz, number, removed)?;
+        quiz.questions.remove(number - 1);
+        Ok(())
+    })
+    .map_err(|source| QuizError::Save {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    say(
+        inter,
+        Message::new("edit-removed")
+            .with("number", number)
+            .with("text", removed.text.as_str()),
+    );
+    check_io(inter)
+}
+
+/// Checks that question `number` is still `original`, since another session
+/// may have changed or removed it after the quiz was loaded.
+fn check_unchanged(quiz: &Quiz, number: usize, original: &Question) -> anyhow::Result<()> {
+    if quiz.questions.get(number - 1) == Some(original) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "question {} was changed by someone else in the meantime, try again",
+            number
+        ))
+    }
+}
+
+/// Loads the quiz, checking that it has a question `number`.
+fn load_for_change(
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    number: usize,
+) -> Result<Quiz, QuizError> {
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    if number == 0 || number > quiz.len() {
+        return Err(QuizError::NoSuchQuestion {
+            number,
+            count: quiz.len(),
+        });
+    }
+
+    Ok(quiz)
+}
+
+/// Asks for a new difficulty until a valid one is given, keeping `current`
+/// if the answer is blank. Returns `None` if there is no more input.
+fn read_difficulty(
+    inter: &mut dyn Interface,
+    current: Option<Difficulty>,
+) -> Option<Option<Difficulty>> {
+    let shown = current.map(|d| d.to_string()).unwrap_or_default();
+    loop {
+        say(
+            inter,
+            Message::new("edit-difficulty").with("current", or_none(&shown)),
+        );
+        let ans = inter.read_stdin_line()?;
+        match ans.trim() {
+            "" => return Some(current),
+            "-" => return Some(None),
+            ans => match ans.parse() {
+                Ok(difficulty) => return Some(Some(difficulty)),
+                Err(_) => say(
+                    inter,
+                    Message::new("add-unknown-difficulty").with("difficulty", ans.to_lowercase()),
+                ),
+            },
+        }
+    }
+}
+
+/// Asks for a new value of something a question can go without, such as its
+/// explanation, with the prompt `id`. Keeps `current` if the answer is blank
+/// and clears it if the answer is '-'. Returns `None` if there is no more
+/// input.
+fn read_note(
+    inter: &mut dyn Interface,
+    id: &'static str,
+    current: &Option<String>,
+) -> Option<Option<String>> {
+    say(
+        inter,
+        Message::new(id).with("current", or_none(current.as_deref().unwrap_or_default())),
+    );
+    let ans = inter.read_stdin_line()?;
+    match ans.trim() {
+        "" => Some(current.clone()),
+        "-" => Some(None),
+        ans => Some(Some(ans.to_string())),
+    }
+}
+
+/// Writes `message` in the user's language.
+fn say(inter: &mut dyn Interface, message: Message) {
+    let msg = inter.messages().text(&message);
+    inter.write_stdout_line(&msg);
+}
+
+/// Shows a current `value`, or that there is none.
+fn or_none(value: &str) -> Arg {
+    if value.is_empty() {
+        Message::new("edit-none").into()
+    } else {
+        value.into()
+    }
+}
+
+/// Reads a line, keeping `current` if it is blank.
+fn read_or_keep(inter: &mut dyn Interface, current: &str) -> Option<String> {
+    let line = inter.read_stdin_line()?;
+    if line.trim().is_empty() {
+        Some(current.to_string())
+    } else {
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":1,"questions":[
+        {"text":"What's my nmae?","correct":"quizzer","incorrects":["andy","sam","joe"]},
+        {"text":"True?","correct":"yes","incorrects":["no"]}
+    ]}"#;
+
+    fn saved(store: &MemoryStore) -> serde_json::Value {
+        serde_json::from_str(&store.quiz_file()).expect("Failed to deserialize quiz file")
+    }
+
+    #[test]
+    fn edit_keeps_blank_answers_and_applies_changes() {
+        let mut inter = FakeInterface::with_stdin(
+            "\
+            What's my name?\n\
+            \n\
+            \n\
+            -\n\
+            bob\n\
+            max\n\
+            \n\
+            \n\
+            \n\
+            Names are easy to misspell.\n\
+            \n\
+        ",
+        );
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 1).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Editing question 1. Leave an answer blank to keep the current value.\n\
+            Question [What's my nmae?]:\n\
+            Correct answer [quizzer]:\n\
+            Incorrect answer 1 [andy] (enter '-' to remove it):\n\
+            Incorrect answer 2 [sam] (enter '-' to remove it):\n\
+            Incorrect answer 3 [joe] (enter '-' to remove it):\n\
+            Add an incorrect answer (leave blank to finish):\n\
+            Add an incorrect answer (leave blank to finish):\n\
+            Tags [none] (enter '-' to clear them):\n\
+            Difficulty [none] (easy, medium or hard, enter '-' to clear it):\n\
+            Explanation [none] (enter '-' to clear it):\n\
+            Reference [none] (enter '-' to clear it):\n\
+            Question 1 updated!\n\
+            "
+        );
+        assert_eq!(
+            saved(&store)["questions"][0],
+            json!({
+                "text": "What's my name?",
+                "kind": "multiple_choice",
+                "correct": "quizzer",
+                "incorrects": ["andy", "bob", "max"],
+                "explanation": "Names are easy to misspell.",
+            })
+        );
+    }
+
+    #[test]
+    fn edit_requires_an_incorrect_answer() {
+        let mut inter = FakeInterface::with_stdin("\n\n-\n\nmaybe\n\nlogic, basics\nmedium\n\n\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 2).unwrap();
+
+        assert!(
+            inter
+                .stdout()
+                .contains("At least one incorrect answer is required.\n")
+        );
+        assert_eq!(
+            saved(&store)["questions"][1],
+            json!({
+                "text": "True?",
+                "kind": "multiple_choice",
+                "correct": "yes",
+                "incorrects": ["maybe"],
+                "tags": ["logic", "basics"],
+                "difficulty": "medium",
+            })
+        );
+    }
+
+    #[test]
+    fn edit_does_not_save_an_incorrect_answer_that_is_correct() {
+        let mut inter = FakeInterface::with_stdin("\n\nYes\n\n\n\n\n\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        let err = edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 2).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Question 2 was not updated: the incorrect answer 'Yes' is also a correct answer."
+        );
+        assert_eq!(store.quiz_file(), QUIZ);
+    }
+
+    #[test]
+    fn edit_changes_the_answers_of_every_kind() {
+        let quiz = r#"{"version":3,"questions":[
+            {"text":"Primes?","kind":"multi_select","corrects":["2","3"],"incorrects":["4"]},
+            {"text":"Capital of France?","kind":"free_text","accepted":["Paris"],"pattern":"paris|parijs"},
+            {"text":"1/3?","kind":"numeric","answer":0.33,"tolerance":0.01}
+        ]}"#;
+        let cases = [
+            (
+                1,
+                "\n-\n\n5\n\n\n6\n\ny\n\n\n\n\n",
+                json!({
+                    "text": "Primes?",
+                    "kind": "multi_select",
+                    "corrects": ["3", "5"],
+                    "incorrects": ["4", "6"],
+                    "partial": true,
+                }),
+            ),
+            (
+                2,
+                "\n-\nParis\n\n-\n\n\n\n\n",
+                json!({ "text": "Capital of France?", "kind": "free_text", "accepted": ["Paris"] }),
+            ),
+            (
+                3,
+                "\nthird\n0.333\n-1\n0.001\n\n\n\n\n",
+                json!({ "text": "1/3?", "kind": "numeric", "answer": 0.333, "tolerance": 0.001 }),
+            ),
+        ];
+
+        for (number, stdin, expected) in cases {
+            let mut inter = FakeInterface::with_stdin(stdin);
+            let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+            edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, number).unwrap();
+
+            assert_eq!(saved(&store)["questions"][number - 1], expected);
+        }
+    }
+
+    #[test]
+    fn edit_prompts_show_the_current_answers() {
+        let quiz = r#"{"version":3,"questions":[
+            {"text":"1/3?","kind":"numeric","answer":0.33,"tolerance":0.01}
+        ]}"#;
+        let mut inter = FakeInterface::with_stdin("\nthird\n\n-1\n\n");
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 1).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Editing question 1. Leave an answer blank to keep the current value.\n\
+            Question [1/3?]:\n\
+            Correct number [0.33]:\n\
+            'third' is not a number.\n\
+            Correct number [0.33]:\n\
+            How far off an answer can be [0.01]:\n\
+            '-1' is not a number of zero or more.\n\
+            How far off an answer can be [0.01]:\n\
+            Tags [none] (enter '-' to clear them):\n\
+            "
+        );
+        assert_eq!(store.quiz_file(), quiz);
+    }
+
+    #[test]
+    fn edit_can_clear_tags_difficulty_and_notes() {
+        let quiz = r#"{"version":1,"questions":[
+            {"text":"True?","correct":"yes","incorrects":["no"],"tags":["logic"],"difficulty":"easy",
+             "explanation":"It is.","reference":"Chapter 2"}
+        ]}"#;
+        let mut inter = FakeInterface::with_stdin("\n\n\n\n-\nwhatever\n-\n-\n-\n");
+        let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 1).unwrap();
+
+        assert!(inter.stdout().ends_with(
+            "\
+            Tags [logic] (enter '-' to clear them):\n\
+            Difficulty [easy] (easy, medium or hard, enter '-' to clear it):\n\
+            unknown difficulty 'whatever', expected easy, medium or hard.\n\
+            Difficulty [easy] (easy, medium or hard, enter '-' to clear it):\n\
+            Explanation [It is.] (enter '-' to clear it):\n\
+            Reference [Chapter 2] (enter '-' to clear it):\n\
+            Question 1 updated!\n\
+            "
+        ));
+        assert_eq!(
+            saved(&store)["questions"][0],
+            json!({ "text": "True?", "kind": "multiple_choice", "correct": "yes", "incorrects": ["no"] })
+        );
+    }
+
+    #[test]
+    fn edit_without_input_does_not_save() {
+        let mut inter = FakeInterface::with_stdin("Changed?\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 2).unwrap();
+
+        assert_eq!(saved(&store)["questions"][1]["text"], "True?");
+    }
+
+    #[test]
+    fn remove_deletes_question() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        remove_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 1).unwrap();
+
+        assert_eq!(inter.stdout(), "Removed question 1: What's my nmae?\n");
+        assert_eq!(
+            saved(&store)["questions"],
+            json!([{ "text": "True?", "kind": "multiple_choice", "correct": "yes", "incorrects": ["no"] }])
+        );
+    }
+
+    #[test]
+    fn out_of_range_question_is_reported() {
+        for number in [0, 3] {
+            let mut inter = FakeInterface::new();
+            let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+            let err =
+                remove_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, number).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "There is no question {}. The quiz has 2 questions, see ./quizzer list",
+                    number
+                )
+            );
+            assert_eq!(inter.stdout(), "");
+        }
+    }
+
+    #[test]
+    fn edit_keeps_questions_added_meanwhile() {
+        let added = r#"{"version":1,"questions":[
+            {"text":"What's my nmae?","correct":"quizzer","incorrects":["andy","sam","joe"]},
+            {"text":"True?","correct":"yes","incorrects":["no"]},
+            {"text":"New?","correct":"yes","incorrects":["no"]}
+        ]}"#;
+        let mut inter = FakeInterface::with_stdin("Really true?\n\n\n\n\n\n\n\n");
+        let mut store = MemoryStore::new()
+            .with_quiz_file(QUIZ)
+            .with_change_before_update(DEFAULT_QUIZ_FILE, added);
+
+        edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 2).unwrap();
+
+        assert!(inter.stdout().ends_with("Question 2 updated!\n"));
+        let saved = saved(&store);
+        assert_eq!(saved["questions"][1]["text"], "Really true?");
+        assert_eq!(saved["questions"][2]["text"], "New?");
+    }
+
+    #[test]
+    fn edit_does_not_overwrite_question_changed_meanwhile() {
+        let changed = r#"{"version":1,"questions":[
+            {"text":"What's my nmae?","correct":"quizzer","incorrects":["andy","sam","joe"]},
+            {"text":"False?","correct":"no","incorrects":["yes"]}
+        ]}"#;
+        let mut inter = FakeInterface::with_stdin("Really true?\n\n\n\n\n\n\n\n");
+        let mut store = MemoryStore::new()
+            .with_quiz_file(QUIZ)
+            .with_change_before_update(DEFAULT_QUIZ_FILE, changed);
+
+        let err = edit_question(&mut inter, &mut store, DEFAULT_QUIZ_FILE, 2).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to save quiz to 'quiz.json': question 2 was changed by someone else in the meantime, try again"
+        );
+        assert_eq!(saved(&store)["questions"][1]["text"], "False?");
+    }
+}
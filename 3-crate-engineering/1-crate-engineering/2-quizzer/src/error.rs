@@ -0,0 +1,159 @@
+use std::{fmt, io};
+
+use crate::{
+    convert::RowError,
+    i18n::{Message, Messages},
+    interface::Interface,
+    seal::Locked,
+};
+
+/// Why a command failed.
+#[derive(Debug)]
+pub enum QuizError {
+    /// The quiz file could not be read or parsed.
+    Load { file: String, source: anyhow::Error },
+    /// The quiz file could not be written.
+    Save { file: String, source: anyhow::Error },
+    /// The study log could not be written.
+    StudyLog { file: String, source: anyhow::Error },
+    /// The results of a quiz run could not be written.
+    Results { file: String, source: anyhow::Error },
+    /// The quiz cannot be served on the given address.
+    Serve {
+        address: String,
+        source: anyhow::Error,
+    },
+    /// A question given on the command line cannot be added, or an edited
+    /// question cannot be saved.
+    InvalidQuestion(Message),
+    /// The quiz has no question with this number, counting from 1.
+    NoSuchQuestion { number: usize, count: usize },
+    /// The format of a file to import could not be told from its name.
+    UnknownFormat(String),
+    /// Some of the questions to import are invalid.
+    Import { file: String, errors: Vec<RowError> },
+    /// The quiz could not be written out in the requested format.
+    Export { file: String, source: anyhow::Error },
+    /// The quiz could not be sealed.
+    Seal { file: String, source: anyhow::Error },
+    /// The quiz is sealed with a key, and the right one was not given.
+    Sealed { file: String, reason: Locked },
+    /// Checking the quiz found problems.
+    Problems {
+        file: String,
+        count: usize,
+        fixable: usize,
+    },
+    /// Reading from stdin or writing to stdout failed.
+    Io(io::Error),
+}
+
+impl QuizError {
+    /// Puts the error into words in the language of `messages`. The reasons
+    /// given by the system, such as why a file could not be read, are kept
+    /// as they are.
+    pub fn text(&self, messages: &Messages) -> String {
+        let message = match self {
+            QuizError::Load { file, source } => failed("error-load", file, source),
+            QuizError::Save { file, source } => failed("error-save", file, source),
+            QuizError::StudyLog { file, source } => failed("error-study-log", file, source),
+            QuizError::Results { file, source } => failed("error-results", file, source),
+            QuizError::Serve { address, source } => Message::new("error-serve")
+                .with("address", address.as_str())
+                .with("error", source.to_string()),
+            QuizError::InvalidQuestion(reason) => return capitalize(&messages.text(reason)),
+            QuizError::NoSuchQuestion { number, count } => Message::new("error-no-such-question")
+                .with("number", *number)
+                .with("count", *count),
+            QuizError::UnknownFormat(file) => {
+                Message::new("error-unknown-format").with("file", file.as_str())
+            }
+            QuizError::Import { file, errors } => {
+                let mut text =
+                    messages.text(&Message::new("error-import").with("file", file.as_str()));
+                for e in errors {
+                    text.push_str(&format!("\n  {}", e.text(messages)));
+                }
+                return text;
+            }
+            QuizError::Export { file, source } => failed("error-export", file, source),
+            QuizError::Seal { file, source } => failed("error-seal", file, source),
+            QuizError::Sealed { file, reason } => {
+                let id = match reason {
+                    Locked::NoKey => "error-sealed-no-key",
+                    Locked::WrongKey => "error-sealed-wrong-key",
+                };
+                Message::new(id).with("file", file.as_str())
+            }
+            QuizError::Problems {
+                file,
+                count,
+                fixable,
+            } => Message::new("error-problems")
+                .with("file", file.as_str())
+                .with("count", *count)
+                .with("fixable", *fixable),
+            QuizError::Io(e) => Message::new("error-io").with("error", e.to_string()),
+        };
+        messages.text(&message)
+    }
+}
+
+/// The message that something failed to be done with `file`, and why.
+fn failed(id: &'static str, file: &str, source: &anyhow::Error) -> Message {
+    Message::new(id)
+        .with("file", file)
+        .with("error", source.to_string())
+}
+
+/// Starts `text` with a capital, as the reason a question is invalid may be
+/// a problem written to follow other words.
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Shows the error in English.
+impl fmt::Display for QuizError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(&Messages::default()))
+    }
+}
+
+impl std::error::Error for QuizError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            QuizError::Load { source, .. }
+            | QuizError::Save { source, .. }
+            | QuizError::StudyLog { source, .. }
+            | QuizError::Results { source, .. }
+            | QuizError::Serve { source, .. }
+            | QuizError::Export { source, .. }
+            | QuizError::Seal { source, .. } => Some(source.as_ref()),
+            QuizError::InvalidQuestion(_)
+            | QuizError::NoSuchQuestion { .. }
+            | QuizError::UnknownFormat(_)
+            | QuizError::Import { .. }
+            | QuizError::Sealed { .. }
+            | QuizError::Problems { .. } => None,
+            QuizError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for QuizError {
+    fn from(e: io::Error) -> Self {
+        QuizError::Io(e)
+    }
+}
+
+/// Returns the I/O error the interface ran into, if there was one.
+pub(crate) fn check_io(inter: &mut dyn Interface) -> Result<(), QuizError> {
+    match inter.take_io_error() {
+        Some(e) => Err(QuizError::Io(e)),
+        None => Ok(()),
+    }
+}
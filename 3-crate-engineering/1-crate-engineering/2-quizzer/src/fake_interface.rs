@@ -1,4 +1,12 @@
-use crate::interface::Interface;
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, SystemTime};
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::i18n::{Lang, Messages};
+use crate::interface::{Interface, TimedLine};
 
 pub struct FakeInterface {
     // NOTE: alternatively, hold a &'static str and an iterator over it.
@@ -6,7 +14,19 @@ pub struct FakeInterface {
     stdin: String,
     std_lines_read: usize,
     stdout: String,
-    saved_json: Option<String>,
+    rng: StdRng,
+    now: SystemTime,
+    /// How long the user takes to enter each of the next lines of stdin.
+    delays: VecDeque<Duration>,
+    /// Whether the next line of stdin was entered after a read timed out.
+    /// Like the real terminal, that line is dropped by the next read.
+    late_line: bool,
+    /// The error to fail with once stdin runs out, instead of ending the input.
+    stdin_error: Option<io::ErrorKind>,
+    /// The error every write to stdout fails with, dropping the output.
+    stdout_error: Option<io::ErrorKind>,
+    io_error: Option<io::Error>,
+    messages: Messages,
 }
 
 impl FakeInterface {
@@ -15,60 +35,143 @@ impl FakeInterface {
     }
 
     /// Creates a FakeInterface with the provided text set as the stdin.
+    /// Its random number generator is seeded with 0 so that tests are repeatable.
     pub fn with_stdin(text: &str) -> Self {
         Self {
             stdin: text.to_string(),
             std_lines_read: 0,
             stdout: String::new(),
-            saved_json: None,
+            rng: StdRng::seed_from_u64(0),
+            now: SystemTime::UNIX_EPOCH,
+            delays: VecDeque::new(),
+            late_line: false,
+            stdin_error: None,
+            stdout_error: None,
+            io_error: None,
+            messages: Messages::default(),
         }
     }
 
+    /// Reseeds the random number generator.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Shows the messages in `lang`.
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.messages = Messages::new(lang);
+        self
+    }
+
+    /// Sets the time reported by the fake clock.
+    pub fn with_time(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Sets how many seconds the user takes to enter each line of stdin, in
+    /// order. Reading a line moves the fake clock forward by its delay; lines
+    /// after the last delay are entered instantly.
+    pub fn with_delays(mut self, secs: &[u64]) -> Self {
+        self.delays = secs.iter().map(|s| Duration::from_secs(*s)).collect();
+        self
+    }
+
+    /// Makes reading fail with `kind` once stdin runs out, like a broken
+    /// terminal, instead of reaching the end of input.
+    pub fn with_stdin_error(mut self, kind: io::ErrorKind) -> Self {
+        self.stdin_error = Some(kind);
+        self
+    }
+
+    /// Makes every write to stdout fail with `kind`, like a closed pipe.
+    pub fn with_stdout_error(mut self, kind: io::ErrorKind) -> Self {
+        self.stdout_error = Some(kind);
+        self
+    }
+
     pub fn stdout(&self) -> &str {
         &self.stdout
     }
 
-    pub fn quiz_file(&self) -> String {
-        self.saved_json
-            .clone()
-            .expect("FakeInterface.quiz_file: No json was saved.")
+    /// Skips the line entered after the last read timed out, if any.
+    fn drop_late_line(&mut self) {
+        if std::mem::take(&mut self.late_line) {
+            self.std_lines_read += 1;
+            self.delays.pop_front();
+        }
+    }
+}
+
+impl Default for FakeInterface {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Interface for FakeInterface {
     fn read_stdin_line(&mut self) -> Option<String> {
-        let next = self.stdin.lines().skip(self.std_lines_read).next();
-        match next {
-            Some(s) => {
-                self.std_lines_read += 1;
-                Some(s.to_string())
-            }
-            None => {
-                panic!("This should never happen.")
+        self.drop_late_line();
+        let Some(next) = self
+            .stdin
+            .lines()
+            .nth(self.std_lines_read)
+            .map(str::to_string)
+        else {
+            if let Some(kind) = self.stdin_error {
+                self.io_error.get_or_insert_with(|| io::Error::from(kind));
             }
+            return None;
+        };
+        self.std_lines_read += 1;
+        self.now += self.delays.pop_front().unwrap_or_default();
+        Some(next)
+    }
+
+    /// Times out if the next line's delay is longer than `timeout`. The line
+    /// is then entered late, after the timeout, and dropped by the next read.
+    fn read_stdin_line_timeout(&mut self, timeout: Duration) -> TimedLine {
+        self.drop_late_line();
+        let delay = self.delays.front().copied().unwrap_or_default();
+        if delay <= timeout {
+            return match self.read_stdin_line() {
+                Some(line) => TimedLine::Line(line),
+                None => TimedLine::Closed,
+            };
         }
+
+        self.late_line = true;
+        self.now += timeout;
+        TimedLine::TimedOut
     }
 
     fn write_stdout(&mut self, text: &str) {
+        if let Some(kind) = self.stdout_error {
+            self.io_error.get_or_insert_with(|| io::Error::from(kind));
+            return;
+        }
         self.stdout.push_str(text);
     }
 
     fn write_stdout_line(&mut self, text: &str) {
-        self.stdout.push_str(text);
-        self.stdout.push('\n');
+        self.write_stdout(text);
+        self.write_stdout("\n");
     }
 
-    fn read_quiz_json(&mut self) -> anyhow::Result<String> {
-        match &self.saved_json {
-            Some(contents) => Ok(contents.clone()),
-            None => Ok(String::new()),
-        }
+    fn take_io_error(&mut self) -> Option<io::Error> {
+        self.io_error.take()
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        &mut self.rng
+    }
+
+    fn now(&mut self) -> SystemTime {
+        self.now
     }
 
-    /// Writes quiz contents to the quiz file.
-    /// Relies on [add] to update the existing quiz.
-    fn write_quiz_json(&mut self, text: &str) -> anyhow::Result<()> {
-        self.saved_json = Some(text.to_string());
-        Ok(())
+    fn messages(&self) -> &Messages {
+        &self.messages
     }
 }
@@ -0,0 +1,227 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use std::process;
+
+use crate::store::QuizStore;
+
+/// Keeps each quiz as a JSON file, named by its path.
+pub struct FileStore {
+    /// How many earlier versions of a quiz file to keep when saving it.
+    backups: usize,
+}
+
+impl FileStore {
+    pub fn new() -> Self {
+        Self { backups: 0 }
+    }
+
+    /// Keeps the last `backups` versions of a quiz file when saving it, the
+    /// newest as `<quiz>.bak.1` and the oldest as `<quiz>.bak.<backups>`.
+    /// Only `.json` files are backed up, not the histories and study logs
+    /// kept beside them, which change on every run.
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+}
+
+impl Default for FileStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuizStore for FileStore {
+    fn read_quiz_json(&mut self, quiz: &str) -> anyhow::Result<String> {
+        let mut file = match File::open(quiz) {
+            Ok(f) => f,
+            // A quiz that does not exist yet is empty.
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(String::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        Ok(contents)
+    }
+
+    /// Writes `text` to a temporary file and renames it over the quiz file,
+    /// so that a crash leaves either the old or the new quiz. The quiz is not
+    /// locked and its old contents are not backed up: that is left to
+    /// [`QuizStore::update_quiz_json`], which moves each `<quiz>.bak.N` to
+    /// `.bak.N+1` and keeps the old quiz as `.bak.1`.
+    fn write_quiz_json(&mut self, quiz: &str, text: &str) -> anyhow::Result<()> {
+        write_atomically(quiz, text)?;
+        Ok(())
+    }
+
+    /// Holds a lock on `<quiz>.lock` while reading, updating and writing the
+    /// quiz, and backs up the old contents of a quiz file first if asked to.
+    fn update_quiz_json(
+        &mut self,
+        quiz: &str,
+        update: &mut dyn FnMut(&str) -> anyhow::Result<String>,
+    ) -> anyhow::Result<()> {
+        let _lock = lock(quiz)?;
+        let contents = self.read_quiz_json(quiz)?;
+        let text = update(&contents)?;
+        if !contents.is_empty() && is_quiz_file(Path::new(quiz)) {
+            back_up(quiz, self.backups)?;
+        }
+        write_atomically(quiz, &text)?;
+        Ok(())
+    }
+
+    /// Lists the `.json` files in `dir`.
+    fn list_quiz_files(&mut self, dir: &str) -> anyhow::Result<Vec<String>> {
+        let mut quizzes = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_quiz_file(&path) {
+                // Show files in the current directory by name, as accepted by `--file`.
+                let path = path.strip_prefix(".").unwrap_or(&path);
+                quizzes.push(path.to_string_lossy().into_owned());
+            }
+        }
+        quizzes.sort();
+        Ok(quizzes)
+    }
+}
+
+fn is_quiz_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// Locks `<path>.lock`, waiting for any other quizzer holding it, until the
+/// returned file is dropped. The lock file is left behind, because removing it
+/// could let another quizzer lock a new file while this one is still locked.
+fn lock(path: &str) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(format!("{}.lock", path))?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Writes `text` to a temporary file next to `path` and renames it over
+/// `path`, so that a crash or a full disk leaves either the old or the new
+/// contents, never a truncated file.
+fn write_atomically(path: &str, text: &str) -> io::Result<()> {
+    let tmp = format!("{}.{}.tmp", path, process::id());
+    let res = write_synced(&tmp, text).and_then(|()| fs::rename(&tmp, path));
+    if res.is_err() {
+        // Do not leave half written files around.
+        let _ = fs::remove_file(&tmp);
+    }
+    res
+}
+
+fn write_synced(path: &str, text: &str) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()
+}
+
+/// Copies `path` to `<path>.bak.1`, first moving each older backup `n` to
+/// `n + 1`, up to `keep` backups.
+fn back_up(path: &str, keep: usize) -> io::Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    for n in (1..keep).rev() {
+        let older = backup_path(path, n);
+        if Path::new(&older).exists() {
+            fs::rename(&older, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+fn backup_path(path: &str, n: usize) -> String {
+    format!("{}.bak.{}", path, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn update_replaces_quiz_and_rotates_backups() {
+        let dir = env::temp_dir().join(format!("quizzer-backups-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let quiz = dir.join("quiz.json").to_string_lossy().into_owned();
+        let mut store = FileStore::new().with_backups(2);
+
+        for version in ["1", "2", "3", "4"] {
+            store
+                .update_quiz_json(&quiz, &mut |contents| {
+                    Ok(format!("{}{}", contents, version))
+                })
+                .unwrap();
+        }
+
+        let read = |path: &str| fs::read_to_string(path).unwrap();
+        assert_eq!(read(&quiz), "1234");
+        assert_eq!(read(&backup_path(&quiz, 1)), "123");
+        assert_eq!(read(&backup_path(&quiz, 2)), "12");
+        assert!(!Path::new(&backup_path(&quiz, 3)).exists());
+
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "quiz.json",
+                "quiz.json.bak.1",
+                "quiz.json.bak.2",
+                "quiz.json.lock"
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn history_is_not_backed_up() {
+        let dir = env::temp_dir().join(format!("quizzer-history-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let history = dir.join("quiz.json.history").to_string_lossy().into_owned();
+        fs::write(&history, "runs").unwrap();
+        let mut store = FileStore::new().with_backups(2);
+
+        store
+            .update_quiz_json(&history, &mut |contents| {
+                Ok(format!("{} and more", contents))
+            })
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&history).unwrap(), "runs and more");
+        assert!(!Path::new(&backup_path(&history, 1)).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_update_keeps_quiz() {
+        let dir = env::temp_dir().join(format!("quizzer-failed-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let quiz = dir.join("quiz.json").to_string_lossy().into_owned();
+        fs::write(&quiz, "old").unwrap();
+        let mut store = FileStore::new();
+
+        let res = store.update_quiz_json(&quiz, &mut |_| Err(anyhow::anyhow!("no space")));
+
+        assert!(res.is_err());
+        assert_eq!(fs::read_to_string(&quiz).unwrap(), "old");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,382 @@
+use std::path::Path;
+
+use crate::{
+    error::{QuizError, check_io},
+    interface::Interface,
+    quiz::{Question, fmt_points},
+    results::QuizResults,
+    seal::load_unlocked,
+    store::QuizStore,
+};
+
+/// How a student answered one of the questions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mark {
+    Correct,
+    /// Partial credit for a multi-select question.
+    Partial(f64),
+    Wrong,
+    Unanswered,
+}
+
+impl Mark {
+    fn symbol(self) -> char {
+        match self {
+            Mark::Correct => '+',
+            Mark::Partial(_) => '~',
+            Mark::Wrong => '-',
+            Mark::Unanswered => '.',
+        }
+    }
+
+    fn points(self) -> f64 {
+        match self {
+            Mark::Correct => 1.0,
+            Mark::Partial(points) => points,
+            Mark::Wrong | Mark::Unanswered => 0.0,
+        }
+    }
+}
+
+/// A graded answer sheet.
+struct Sheet {
+    student: String,
+    marks: Vec<Mark>,
+}
+
+impl Sheet {
+    fn score(&self) -> f64 {
+        self.marks.iter().map(|m| m.points()).sum()
+    }
+}
+
+/// Grades answer sheets against the quiz in `quiz_file`, without prompting,
+/// and prints a summary per student and per question.
+///
+/// Each sheet has one answer per line, line `n` answering question `n`. An
+/// answer is the full text of the chosen answer, since the choice labels are
+/// shuffled on every run, with the picks of a multi-select question separated
+/// by commas. Blank lines are unanswered questions. A `.json` sheet is instead
+/// the results of a run written with `quiz --results`, such as one recording
+/// the answers to a quiz sealed with a key taken without it. The student is
+/// named after the sheet's file name. The answers of a quiz sealed with a key
+/// are checked with `key`.
+pub fn grade(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    answer_files: &[String],
+    key: Option<&str>,
+) -> Result<(), QuizError> {
+    let quiz = load_unlocked(store, quiz_file, key)?;
+
+    if quiz.is_empty() {
+        let msg = inter.messages().get("no-questions");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    let mut sheets = Vec::with_capacity(answer_files.len());
+    for path in answer_files {
+        let contents = store.read_file(path).map_err(|source| QuizError::Load {
+            file: path.clone(),
+            source,
+        })?;
+
+        let answers = if is_results_file(path) {
+            recorded_answers(&quiz.questions, &contents).map_err(|source| QuizError::Load {
+                file: path.clone(),
+                source,
+            })?
+        } else {
+            let answers: Vec<String> = contents.lines().map(String::from).collect();
+            if answers.len() > quiz.len() {
+                let msg = inter.messages().format(
+                    "grade-extra-answers",
+                    &[
+                        ("file", path.as_str().into()),
+                        ("count", answers.len().into()),
+                        ("total", quiz.len().into()),
+                    ],
+                );
+                inter.write_stdout_line(&msg);
+            }
+            answers
+        };
+        let answers: Vec<&str> = answers.iter().map(String::as_str).collect();
+
+        sheets.push(Sheet {
+            student: student_name(path),
+            marks: mark_answers(&quiz.questions, &answers),
+        });
+    }
+
+    print_summary(inter, &quiz.questions, &sheets);
+    check_io(inter)
+}
+
+/// Tells whether `path` is the JSON results of a quiz run rather than a
+/// plain answer sheet.
+fn is_results_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Returns the answers recorded in the JSON results of a quiz run to each of
+/// `questions`, found by the text of the question as the run may have asked
+/// only some of them, in any order.
+fn recorded_answers(questions: &[Question], json: &str) -> anyhow::Result<Vec<String>> {
+    let results: QuizResults = serde_json::from_str(json)?;
+    Ok(questions
+        .iter()
+        .map(|q| {
+            results
+                .questions
+                .iter()
+                .find(|r| r.question == q.text)
+                .and_then(|r| r.answer.clone())
+                .unwrap_or_default()
+        })
+        .collect())
+}
+
+/// Marks the answers to each of `questions`; missing answers are unanswered.
+fn mark_answers(questions: &[Question], answers: &[&str]) -> Vec<Mark> {
+    questions
+        .iter()
+        .enumerate()
+        .map(|(idx, q)| match answers.get(idx).map(|a| a.trim()) {
+            None | Some("") => Mark::Unanswered,
+            Some(ans) => match q.kind.score_text(ans) {
+                points if points >= 1.0 => Mark::Correct,
+                points if points > 0.0 => Mark::Partial(points),
+                _ => Mark::Wrong,
+            },
+        })
+        .collect()
+}
+
+/// Names a student after their answer sheet, e.g. `answers/alice.txt` is alice.
+fn student_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn print_summary(inter: &mut dyn Interface, questions: &[Question], sheets: &[Sheet]) {
+    let total = questions.len();
+    let messages = inter.messages();
+    let [student, score, answers, question, correct, legend] = [
+        "grade-student",
+        "grade-score",
+        "grade-answers",
+        "grade-question",
+        "grade-correct",
+        "grade-legend",
+    ]
+    .map(|id| messages.get(id));
+
+    let name_width = sheets
+        .iter()
+        .map(|s| s.student.chars().count())
+        .chain([student.chars().count()])
+        .max()
+        .unwrap_or_default();
+    let scores: Vec<String> = sheets
+        .iter()
+        .map(|s| format!("{}/{}", fmt_points(s.score()), total))
+        .collect();
+    let score_width = scores
+        .iter()
+        .map(String::len)
+        .chain([score.chars().count()])
+        .max()
+        .unwrap_or_default();
+    inter.write_stdout_line(&format!(
+        "{:name_width$}  {:score_width$}  {}",
+        student, score, answers
+    ));
+    for (sheet, score) in sheets.iter().zip(&scores) {
+        let marks: Vec<String> = sheet.marks.iter().map(|m| m.symbol().to_string()).collect();
+        inter.write_stdout_line(&format!(
+            "{:name_width$}  {:score_width$}  {}",
+            sheet.student,
+            score,
+            marks.join(" ")
+        ));
+    }
+    inter.write_stdout_line("");
+
+    let label_width = questions
+        .iter()
+        .enumerate()
+        .map(|(idx, q)| format!("{}. {}", idx + 1, q.text).chars().count())
+        .chain([question.chars().count()])
+        .max()
+        .unwrap_or_default();
+    inter.write_stdout_line(&format!("{:label_width$}  {}", question, correct));
+    for (idx, q) in questions.iter().enumerate() {
+        let correct = sheets
+            .iter()
+            .filter(|s| s.marks[idx] == Mark::Correct)
+            .count();
+        inter.write_stdout_line(&format!(
+            "{:label_width$}  {}/{}",
+            format!("{}. {}", idx + 1, q.text),
+            correct,
+            sheets.len()
+        ));
+    }
+    inter.write_stdout_line("");
+
+    inter.write_stdout_line(&legend);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":1,"questions":[
+        {"text":"What's my name?","correct":"quizzer","incorrects":["andy","sam"]},
+        {"text":"True?","correct":"yes","incorrects":["no"]},
+        {"text":"2 + 2?","correct":"4","incorrects":["3"]}
+    ]}"#;
+
+    #[test]
+    fn grades_each_sheet_and_question() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new()
+            .with_quiz_file(QUIZ)
+            .with_file("answers/alice.txt", "Quizzer\nyes\n4\n")
+            .with_file("answers/bob.txt", "andy\n\n  4 \n");
+
+        grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &[
+                "answers/alice.txt".to_string(),
+                "answers/bob.txt".to_string(),
+            ],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Student  Score  Answers\n\
+            alice    3/3    + + +\n\
+            bob      1/3    - . +\n\
+            \n\
+            Question            Correct\n\
+            1. What's my name?  1/2\n\
+            2. True?            1/2\n\
+            3. 2 + 2?           2/2\n\
+            \n\
+            + correct, ~ partly correct, - wrong, . unanswered\n\
+            "
+        );
+    }
+
+    #[test]
+    fn missing_answers_are_unanswered_and_extra_ones_ignored() {
+        let questions: Vec<Question> = crate::schema::parse_quiz(QUIZ).unwrap().questions;
+
+        assert_eq!(
+            mark_answers(&questions, &["quizzer"]),
+            [Mark::Correct, Mark::Unanswered, Mark::Unanswered]
+        );
+
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new()
+            .with_quiz_file(QUIZ)
+            .with_file("carol.txt", "quizzer\nyes\n4\n5\n");
+        grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &["carol.txt".to_string()],
+            None,
+        )
+        .unwrap();
+
+        assert!(inter.stdout().starts_with(
+            "carol.txt has 4 answers but the quiz only has 3 questions, ignoring the rest.\n"
+        ));
+    }
+
+    #[test]
+    fn missing_answer_file_is_an_error() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        let err = grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &["nobody.txt".to_string()],
+            None,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "Failed to load nobody.txt: No such file");
+    }
+
+    #[test]
+    fn failed_output_is_an_error() {
+        for quiz in [QUIZ, ""] {
+            let mut inter = FakeInterface::new().with_stdout_error(io::ErrorKind::BrokenPipe);
+            let mut store = MemoryStore::new()
+                .with_quiz_file(quiz)
+                .with_file("alice.txt", "quizzer\n");
+
+            let err = grade(
+                &mut inter,
+                &mut store,
+                DEFAULT_QUIZ_FILE,
+                &["alice.txt".to_string()],
+                None,
+            )
+            .unwrap_err();
+
+            assert!(matches!(err, QuizError::Io(_)), "{}", err);
+        }
+    }
+
+    #[test]
+    fn partial_answers_score_part_of_a_point() {
+        let quiz = r#"{"version":2,"questions":[
+            {"text":"Primes?","kind":"multi_select","corrects":["2","3"],"incorrects":["4"],"partial":true},
+            {"text":"1/3?","kind":"numeric","answer":0.33,"tolerance":0.01}
+        ]}"#;
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new()
+            .with_quiz_file(quiz)
+            .with_file("dave.txt", "2\n0.33\n");
+
+        grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &["dave.txt".to_string()],
+            None,
+        )
+        .unwrap();
+
+        assert!(inter.stdout().starts_with(
+            "\
+            Student  Score  Answers\n\
+            dave     1.5/2  ~ +\n\
+            "
+        ));
+    }
+}
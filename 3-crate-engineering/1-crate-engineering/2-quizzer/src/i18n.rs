@@ -0,0 +1,422 @@
+//! The messages shown while adding questions, taking quizzes and studying, in
+//! English or Dutch.
+//!
+//! Each language has a Fluent catalogue in `locales/`, built into the binary,
+//! and messages are looked up in it by id.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use clap::ValueEnum;
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+/// A language the messages are available in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    #[default]
+    En,
+    Nl,
+}
+
+impl Lang {
+    /// Picks the language of a locale such as `nl_NL.UTF-8`, as found in
+    /// `LANG`. Returns `None` for locales in other languages.
+    pub fn from_locale(locale: &str) -> Option<Lang> {
+        let lang = locale.split(['_', '-', '.', '@']).next()?;
+        match lang.to_lowercase().as_str() {
+            "en" => Some(Lang::En),
+            "nl" => Some(Lang::Nl),
+            _ => None,
+        }
+    }
+
+    fn catalogue(self) -> &'static str {
+        match self {
+            Lang::En => include_str!("../locales/en.ftl"),
+            Lang::Nl => include_str!("../locales/nl.ftl"),
+        }
+    }
+
+    fn id(self) -> LanguageIdentifier {
+        let id = match self {
+            Lang::En => "en",
+            Lang::Nl => "nl",
+        };
+        id.parse().expect("language ids are valid")
+    }
+}
+
+/// A message by id, with its arguments, for code that does not know the
+/// user's language to hand to code that does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub id: &'static str,
+    pub args: Vec<(&'static str, Arg)>,
+}
+
+/// The value of one of a message's arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    Text(String),
+    Number(f64),
+    /// Another message, put into words in the same language.
+    Message(Box<Message>),
+}
+
+impl Message {
+    pub fn new(id: &'static str) -> Self {
+        Self {
+            id,
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds the argument `name`.
+    pub fn with(mut self, name: &'static str, value: impl Into<Arg>) -> Self {
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+/// Shows the message in English, such as in tests.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Messages::default().text(self))
+    }
+}
+
+impl From<&str> for Arg {
+    fn from(text: &str) -> Self {
+        Arg::Text(text.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(text: String) -> Self {
+        Arg::Text(text)
+    }
+}
+
+impl From<usize> for Arg {
+    fn from(n: usize) -> Self {
+        Arg::Number(n as f64)
+    }
+}
+
+impl From<f64> for Arg {
+    fn from(n: f64) -> Self {
+        Arg::Number(n)
+    }
+}
+
+impl From<Message> for Arg {
+    fn from(message: Message) -> Self {
+        Arg::Message(Box::new(message))
+    }
+}
+
+/// The catalogue of messages in one language.
+pub struct Messages {
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Messages {
+    pub fn new(lang: Lang) -> Self {
+        let resource = FluentResource::try_new(lang.catalogue().to_string())
+            .unwrap_or_else(|(_, errors)| panic!("invalid {:?} catalogue: {:?}", lang, errors));
+        let mut bundle = FluentBundle::new(vec![lang.id()]);
+        // The marks Fluent puts around arguments for right-to-left text show
+        // up as stray characters in some terminals.
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .unwrap_or_else(|errors| panic!("invalid {:?} catalogue: {:?}", lang, errors));
+        Self { bundle }
+    }
+
+    /// Returns the message `id`.
+    pub fn get(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+
+    /// Returns the message `id` with its arguments filled in. A message that
+    /// is missing from the catalogue is shown as its id.
+    pub fn format(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let Some(pattern) = self.bundle.get_message(id).and_then(|m| m.value()) else {
+            return id.to_string();
+        };
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        let text: Cow<str> = self
+            .bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors);
+        text.into_owned()
+    }
+
+    /// Puts `message` into words.
+    pub fn text(&self, message: &Message) -> String {
+        let args: Vec<(&str, FluentValue)> = message
+            .args
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Arg::Text(text) => FluentValue::from(text.as_str()),
+                    Arg::Number(n) => FluentValue::from(*n),
+                    Arg::Message(message) => FluentValue::from(self.text(message)),
+                };
+                (*name, value)
+            })
+            .collect();
+        self.format(message.id, &args)
+    }
+
+    /// Returns true if `answer` is yes to a (y/n) question. The English "y"
+    /// is always understood, as well as the letter of the language.
+    pub fn is_yes(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+        answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case(&self.get("yes"))
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self::new(Lang::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    use regex::Regex;
+
+    use super::*;
+    use crate::add::add;
+    use crate::check::check;
+    use crate::error::QuizError;
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, QuizOptions, run_quiz};
+
+    /// The ids of the messages in a catalogue, each with the names of the
+    /// arguments it uses.
+    fn message_ids(lang: Lang) -> BTreeSet<(String, BTreeSet<String>)> {
+        let arg = Regex::new(r"\$([a-z-]+)").unwrap();
+        let mut messages = Vec::new();
+        for line in lang.catalogue().lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some((id, _)) = line.split_once(" =").filter(|_| !line.starts_with(' ')) {
+                messages.push((id.to_string(), BTreeSet::new()));
+            }
+            if let Some((_, args)) = messages.last_mut() {
+                args.extend(arg.captures_iter(line).map(|c| c[1].to_string()));
+            }
+        }
+        messages.into_iter().collect()
+    }
+
+    #[test]
+    fn catalogues_have_the_same_messages() {
+        assert_eq!(message_ids(Lang::Nl), message_ids(Lang::En));
+    }
+
+    /// The message ids used by the code outside of tests, found as string
+    /// literals that start like the ids in the catalogue.
+    fn used_ids(dir: &Path, prefixes: &BTreeSet<&str>, ids: &mut BTreeSet<String>) {
+        let literal = Regex::new(r#""([a-z]+(-[a-z]+)+)""#).unwrap();
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                used_ids(&path, prefixes, ids);
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+            ids.extend(
+                literal
+                    .captures_iter(code)
+                    .filter(|c| c[1].split('-').next().is_some_and(|p| prefixes.contains(p)))
+                    .map(|c| c[1].to_string()),
+            );
+        }
+    }
+
+    #[test]
+    fn catalogues_have_the_messages_the_code_uses() {
+        let catalogue: BTreeSet<String> = message_ids(Lang::En)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let prefixes = catalogue
+            .iter()
+            .filter_map(|id| id.split_once('-'))
+            .map(|(prefix, _)| prefix)
+            .collect();
+        let mut used = BTreeSet::new();
+        used_ids(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &prefixes,
+            &mut used,
+        );
+
+        assert_eq!(
+            used.difference(&catalogue).collect::<Vec<_>>(),
+            Vec::<&String>::new(),
+            "used but missing from the catalogues"
+        );
+        assert_eq!(
+            catalogue
+                .iter()
+                .filter(|id| id.contains('-'))
+                .filter(|id| !used.contains(*id))
+                .collect::<Vec<_>>(),
+            Vec::<&String>::new(),
+            "in the catalogues but never used"
+        );
+    }
+
+    #[test]
+    fn language_is_taken_from_the_locale() {
+        assert_eq!(Lang::from_locale("nl_NL.UTF-8"), Some(Lang::Nl));
+        assert_eq!(Lang::from_locale("nl-BE"), Some(Lang::Nl));
+        assert_eq!(Lang::from_locale("en_GB"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("C"), None);
+        assert_eq!(Lang::from_locale("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn messages_fill_in_arguments() {
+        let messages = Messages::new(Lang::Nl);
+
+        assert_eq!(
+            messages.format("quiz-score", &[("score", "2".into()), ("total", 3.into())]),
+            "Je hebt 2 van de 3 punten gehaald."
+        );
+        assert_eq!(messages.get("no-such-message"), "no-such-message");
+        assert!(messages.is_yes(" J "));
+        assert!(messages.is_yes("y"));
+        assert!(!Messages::new(Lang::En).is_yes("j"));
+    }
+
+    #[test]
+    fn errors_are_shown_in_either_language() {
+        let err = QuizError::NoSuchQuestion {
+            number: 3,
+            count: 2,
+        };
+
+        assert_eq!(
+            err.text(&Messages::new(Lang::Nl)),
+            "Er is geen vraag 3. De quiz heeft 2 vragen, zie ./quizzer list"
+        );
+        assert_eq!(
+            err.to_string(),
+            "There is no question 3. The quiz has 2 questions, see ./quizzer list"
+        );
+    }
+
+    #[test]
+    fn problems_are_reported_in_either_language() {
+        let quiz = r#"{"version":2,"questions":[
+            {"text":"True?","kind":"multiple_choice","correct":"yes","incorrects":["no","no"]}
+        ]}"#;
+        for (lang, expected) in [
+            (
+                Lang::En,
+                "Question 1: the incorrect answer 'no' is repeated (fixable)\n",
+            ),
+            (
+                Lang::Nl,
+                "Vraag 1: het foute antwoord 'no' komt twee keer voor (op te lossen)\n",
+            ),
+        ] {
+            let mut inter = FakeInterface::new().with_lang(lang);
+            let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+            check(&mut inter, &mut store, DEFAULT_QUIZ_FILE, false).unwrap_err();
+
+            assert_eq!(inter.stdout(), expected);
+        }
+
+        let err = QuizError::InvalidQuestion(
+            Message::new("add-invalid").with("problem", Message::new("problem-empty-correct")),
+        );
+        assert_eq!(
+            err.text(&Messages::new(Lang::Nl)),
+            "Het goede antwoord is leeg."
+        );
+    }
+
+    const ADD_STDIN: &str = "What is 2 + 2?\n\n4\n5\n\n\n\n\n\nn\n";
+
+    #[test]
+    fn questions_are_added_in_either_language() {
+        for (lang, prompt, added, again) in [
+            (
+                Lang::En,
+                "Enter a new question:",
+                "Question added!",
+                "Continue adding questions? (y/n)",
+            ),
+            (
+                Lang::Nl,
+                "Typ een nieuwe vraag:",
+                "Vraag toegevoegd!",
+                "Nog een vraag toevoegen? (j/n)",
+            ),
+        ] {
+            let mut inter = FakeInterface::with_stdin(ADD_STDIN).with_lang(lang);
+            let mut store = MemoryStore::new();
+
+            add(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+            let lines: Vec<&str> = inter.stdout().lines().collect();
+            assert_eq!(lines[0], prompt, "{:?}", lang);
+            assert_eq!(lines[lines.len() - 2..], [added, again], "{:?}", lang);
+            assert!(store.saved_quiz_file().unwrap().contains("What is 2 + 2?"));
+        }
+    }
+
+    #[test]
+    fn quizzes_are_run_in_either_language() {
+        let quiz = r#"{"version":3,"questions":[
+            {"text":"2 + 2?","kind":"numeric","answer":4,"explanation":"Count them."},
+            {"text":"Capital of France?","kind":"free_text","accepted":["Paris"]}
+        ]}"#;
+        for (lang, expected) in [
+            (
+                Lang::En,
+                "Correct!\nExplanation: Count them.\n\n\
+                 Capital of France?\n>>> Wrong answer.\n\n\
+                 You scored 1 out of 2 points.\n",
+            ),
+            (
+                Lang::Nl,
+                "Goed!\nUitleg: Count them.\n\n\
+                 Capital of France?\n>>> Fout antwoord.\n\n\
+                 Je hebt 1 van de 2 punten gehaald.\n",
+            ),
+        ] {
+            let mut inter = FakeInterface::with_stdin("4\nLyon\n").with_lang(lang);
+            let mut store = MemoryStore::new().with_quiz_file(quiz);
+
+            run_quiz(
+                &mut inter,
+                &mut store,
+                DEFAULT_QUIZ_FILE,
+                &QuizOptions::default(),
+            )
+            .unwrap();
+
+            assert_eq!(inter.stdout(), format!("2 + 2?\n>>> {}", expected));
+        }
+    }
+}
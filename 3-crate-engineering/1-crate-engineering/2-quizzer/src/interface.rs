@@ -1,7 +1,61 @@
+use std::io;
+use std::time::{Duration, SystemTime};
+
+use rand::RngCore;
+
+use crate::i18n::Messages;
+use crate::quiz::Outcome;
+
+/// The result of reading a line with a time limit.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TimedLine {
+    Line(String),
+    /// No line was entered in time.
+    TimedOut,
+    /// There is no more input.
+    Closed,
+}
+
+/// A question ready to be asked, for interfaces that show it their own way.
+pub struct Prompt<'a> {
+    pub text: &'a str,
+    /// The choices in the order shown, labelled a, b, c, ... Empty if the
+    /// answer is typed instead.
+    pub choices: &'a [&'a str],
+    /// Whether several of the choices can be picked.
+    pub multi: bool,
+}
+
 pub trait Interface {
+    /// Reads a line, or returns `None` at the end of input. Input also ends
+    /// when reading fails, see [Interface::take_io_error].
     fn read_stdin_line(&mut self) -> Option<String>;
+    /// Reads a line, giving up if none is entered within `timeout`.
+    fn read_stdin_line_timeout(&mut self, timeout: Duration) -> TimedLine;
     fn write_stdout(&mut self, text: &str);
     fn write_stdout_line(&mut self, text: &str);
-    fn read_quiz_json(&mut self) -> anyhow::Result<String>;
-    fn write_quiz_json(&mut self, text: &str) -> anyhow::Result<()>;
+    /// Shows `prompt` and reads the answer, giving up after `timeout` if there
+    /// is one. Returns `None` if the interface has no way of its own to ask
+    /// questions, in which case they are written out and answered with a line.
+    fn ask(&mut self, _prompt: &Prompt, _timeout: Option<Duration>) -> Option<TimedLine> {
+        None
+    }
+    /// Tells the user how they answered.
+    fn show_feedback(&mut self, _outcome: Outcome, message: &str) {
+        self.write_stdout_line(message);
+    }
+    /// Tells the interface how far the quiz has got, before asking the next
+    /// question. Only interfaces that keep a progress display use this.
+    fn show_progress(&mut self, _asked: usize, _total: usize, _score: f64) {}
+    /// Returns and clears the first error hit while reading stdin, or else
+    /// writing stdout. Output is still written after reading fails, but once
+    /// writing has failed, further output is dropped until the error is taken.
+    fn take_io_error(&mut self) -> Option<io::Error>;
+    /// The current time, used to schedule questions when studying and to
+    /// time answers.
+    fn now(&mut self) -> SystemTime;
+    /// The source of randomness used to shuffle answer choices.
+    fn rng(&mut self) -> &mut dyn RngCore;
+    /// The messages to show the user, in their language.
+    fn messages(&self) -> &Messages;
 }
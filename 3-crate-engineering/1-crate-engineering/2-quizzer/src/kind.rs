@@ -0,0 +1,453 @@
+//! The kinds of question a quiz can have, and how their answers are scored.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use clap::ValueEnum;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Message;
+use crate::quiz::{is_correct_text, normalize_answer};
+use crate::seal::{hash_answer, hash_answers};
+
+/// The kinds of question, without their answers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Pick the one correct answer out of the choices
+    #[default]
+    MultipleChoice,
+    /// Pick every correct answer out of the choices
+    MultiSelect,
+    /// Type the answer
+    FreeText,
+    /// Type a number
+    Numeric,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::MultipleChoice => "multiple-choice",
+            Kind::MultiSelect => "multi-select",
+            Kind::FreeText => "free-text",
+            Kind::Numeric => "numeric",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What a question asks for and which answers are right. Stored in the quiz
+/// file next to the question's text, with a `kind` field telling them apart.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuestionKind {
+    MultipleChoice {
+        correct: String,
+        incorrects: Vec<String>,
+    },
+    /// Answered by picking every correct answer. Unless `partial` is set,
+    /// only picking exactly the correct answers scores a point.
+    MultiSelect {
+        corrects: Vec<String>,
+        incorrects: Vec<String>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        partial: bool,
+    },
+    /// Answered by typing one of the `accepted` answers, or anything matching
+    /// `pattern`, ignoring case.
+    FreeText {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        accepted: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+    /// Answered by typing a number at most `tolerance` away from `answer`.
+    Numeric {
+        answer: f64,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        tolerance: f64,
+    },
+    /// A question of kind `of` whose correct answers are only kept as salted
+    /// hashes, see [crate::seal], so that answers can be checked but not read,
+    /// or with a key, checked only by whoever has the key.
+    Sealed {
+        of: Kind,
+        /// Every choice of a multiple-choice or multi-select question, sorted.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        choices: Vec<String>,
+        salt: String,
+        /// The hashes of the correct answers, or of all of them together for
+        /// a multi-select question without partial credit.
+        hashes: Vec<String>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        partial: bool,
+        /// Set if the hashes are keyed with a secret the teacher keeps, to a
+        /// hash that tells whether the right key was given.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key_check: Option<String>,
+        /// The key of a keyed question once it is given, see
+        /// [crate::seal::unlock_quiz]. It is never saved.
+        #[serde(skip)]
+        key: Option<String>,
+    },
+}
+
+impl Default for QuestionKind {
+    fn default() -> Self {
+        QuestionKind::MultipleChoice {
+            correct: String::new(),
+            incorrects: Vec::new(),
+        }
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+fn is_zero(n: &f64) -> bool {
+    *n == 0.0
+}
+
+impl QuestionKind {
+    pub fn kind(&self) -> Kind {
+        match self {
+            QuestionKind::MultipleChoice { .. } => Kind::MultipleChoice,
+            QuestionKind::MultiSelect { .. } => Kind::MultiSelect,
+            QuestionKind::FreeText { .. } => Kind::FreeText,
+            QuestionKind::Numeric { .. } => Kind::Numeric,
+            QuestionKind::Sealed { of, .. } => *of,
+        }
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        matches!(self, QuestionKind::Sealed { .. })
+    }
+
+    /// Returns why the answers cannot be used, if they cannot.
+    pub fn problem(&self) -> Option<Message> {
+        match self {
+            QuestionKind::MultipleChoice {
+                correct,
+                incorrects,
+            } => {
+                if correct.trim().is_empty() {
+                    Some(Message::new("problem-empty-correct"))
+                } else {
+                    incorrects_problem(incorrects)
+                }
+            }
+            QuestionKind::MultiSelect {
+                corrects,
+                incorrects,
+                ..
+            } => {
+                if corrects.is_empty() {
+                    Some(Message::new("problem-corrects-required"))
+                } else if corrects.iter().any(|a| a.trim().is_empty()) {
+                    Some(empty_answer("correct"))
+                } else {
+                    incorrects_problem(incorrects)
+                }
+            }
+            QuestionKind::FreeText { accepted, pattern } => {
+                if accepted.is_empty() && pattern.is_none() {
+                    Some(Message::new("problem-accepted-required"))
+                } else if accepted.iter().any(|a| a.trim().is_empty()) {
+                    Some(empty_answer("accepted"))
+                } else if pattern
+                    .as_deref()
+                    .is_some_and(|p| compile_pattern(p).is_err())
+                {
+                    Some(Message::new("problem-invalid-pattern"))
+                } else {
+                    None
+                }
+            }
+            QuestionKind::Numeric { answer, tolerance } => {
+                if !answer.is_finite() {
+                    Some(Message::new("problem-not-a-number"))
+                } else if *tolerance < 0.0 {
+                    Some(Message::new("problem-negative-tolerance"))
+                } else {
+                    None
+                }
+            }
+            QuestionKind::Sealed { of, choices, .. } => {
+                let has_choices = matches!(of, Kind::MultipleChoice | Kind::MultiSelect);
+                if has_choices && choices.len() < 2 {
+                    Some(Message::new("problem-choices-required"))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Describes the correct answer, for reports.
+    pub fn expected(&self) -> String {
+        match self {
+            QuestionKind::MultipleChoice { correct, .. } => correct.clone(),
+            QuestionKind::MultiSelect { corrects, .. } => corrects.join(", "),
+            QuestionKind::FreeText { accepted, pattern } => {
+                let mut options: Vec<String> = accepted.clone();
+                options.extend(pattern.iter().map(|p| format!("/{}/", p)));
+                options.join(" or ")
+            }
+            QuestionKind::Numeric { answer, tolerance } => {
+                if *tolerance == 0.0 {
+                    answer.to_string()
+                } else {
+                    format!("{} ± {}", answer, tolerance)
+                }
+            }
+            QuestionKind::Sealed { .. } => "hidden, the quiz is sealed".to_string(),
+        }
+    }
+
+    /// Scores an answer given as text rather than by choice label, from 0 for
+    /// wrong to 1 for right. The picks of a multi-select question are
+    /// separated by commas.
+    pub fn score_text(&self, ans: &str) -> f64 {
+        match self {
+            QuestionKind::MultipleChoice { correct, .. } => points(is_correct_text(ans, correct)),
+            QuestionKind::MultiSelect {
+                corrects, partial, ..
+            } => {
+                let picks: Vec<&str> = split_picks(ans).collect();
+                score_picks(corrects, *partial, &picks)
+            }
+            QuestionKind::FreeText { accepted, pattern } => {
+                let matches_pattern = pattern.as_deref().is_some_and(|p| {
+                    compile_pattern(p).is_ok_and(|re| re.is_match(&normalize_spaces(ans)))
+                });
+                points(matches_pattern || accepted.iter().any(|a| is_correct_text(ans, a)))
+            }
+            QuestionKind::Numeric { answer, tolerance } => points(
+                ans.trim()
+                    .parse::<f64>()
+                    .is_ok_and(|n| (n - answer).abs() <= *tolerance + TOLERANCE_SLACK),
+            ),
+            QuestionKind::Sealed { of, .. } => {
+                let picks: Vec<&str> = match of {
+                    Kind::MultiSelect => split_picks(ans).collect(),
+                    _ => vec![ans],
+                };
+                self.score_picks(&picks)
+            }
+        }
+    }
+
+    /// Scores the answers picked for a question with choices, given by their
+    /// full text, from 0 for wrong to 1 for right.
+    pub(crate) fn score_picks(&self, picks: &[&str]) -> f64 {
+        match self {
+            QuestionKind::MultiSelect {
+                corrects, partial, ..
+            } => score_picks(corrects, *partial, picks),
+            QuestionKind::Sealed {
+                of,
+                salt,
+                hashes,
+                partial,
+                key_check,
+                key,
+                ..
+            } => {
+                if key_check.is_some() && key.is_none() {
+                    // Without the key nothing can be checked.
+                    return 0.0;
+                }
+                score_sealed(*of, key.as_deref(), salt, hashes, *partial, picks)
+            }
+            kind => match picks {
+                [pick] => kind.score_text(pick),
+                _ => 0.0,
+            },
+        }
+    }
+}
+
+fn incorrects_problem(incorrects: &[String]) -> Option<Message> {
+    if incorrects.is_empty() {
+        Some(Message::new("problem-incorrects-required"))
+    } else if incorrects.iter().any(|a| a.trim().is_empty()) {
+        Some(empty_answer("incorrect"))
+    } else {
+        None
+    }
+}
+
+/// The problem that one of the `answers`, such as the "correct" ones, is
+/// empty.
+pub(crate) fn empty_answer(answers: &str) -> Message {
+    Message::new("problem-empty-answer").with("answers", answers)
+}
+
+/// Added to every tolerance, so that rounding errors in the subtraction do not
+/// reject answers exactly at the edge, like 0.34 for 0.33 ± 0.01.
+const TOLERANCE_SLACK: f64 = 1e-9;
+
+fn points(correct: bool) -> f64 {
+    if correct { 1.0 } else { 0.0 }
+}
+
+/// Compiles a free-text pattern so that it must match the whole answer,
+/// ignoring case.
+pub fn compile_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&format!("^(?:{})$", pattern))
+        .case_insensitive(true)
+        .build()
+}
+
+fn normalize_spaces(ans: &str) -> String {
+    ans.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits the answers picked for a multi-select question.
+pub(crate) fn split_picks(ans: &str) -> impl Iterator<Item = &str> {
+    ans.split(',').map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// Scores the answers picked for a multi-select question. Without `partial`
+/// credit, exactly the correct answers must be picked. With it, each correct
+/// pick is worth a share of the point and each wrong pick takes one away.
+pub(crate) fn score_picks(corrects: &[String], partial: bool, picks: &[&str]) -> f64 {
+    let picked: BTreeSet<String> = picks.iter().map(|p| normalize_answer(p)).collect();
+    let right = corrects
+        .iter()
+        .filter(|c| picked.contains(&normalize_answer(c)))
+        .count();
+    let wrong = picked.len() - right;
+
+    if partial {
+        ((right as f64 - wrong as f64) / corrects.len() as f64).max(0.0)
+    } else {
+        points(right == corrects.len() && wrong == 0)
+    }
+}
+
+/// Scores the answers given to a sealed question of kind `of` by hashing
+/// them, the way [score_picks] scores those of an unsealed question.
+fn score_sealed(
+    of: Kind,
+    key: Option<&str>,
+    salt: &str,
+    hashes: &[String],
+    partial: bool,
+    picks: &[&str],
+) -> f64 {
+    if of == Kind::MultiSelect && !partial {
+        return points(hashes.contains(&hash_answers(key, salt, picks)));
+    }
+
+    let picked: BTreeSet<String> = picks
+        .iter()
+        .map(|p| hash_answer(key, salt, of, p))
+        .collect();
+    let right = picked.iter().filter(|h| hashes.contains(h)).count();
+    let wrong = picked.len() - right;
+    if of == Kind::MultiSelect {
+        ((right as f64 - wrong as f64) / hashes.len() as f64).max(0.0)
+    } else {
+        points(right > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn multi_select_is_all_or_nothing_unless_partial() {
+        let corrects = strings(&["2", "3", "5"]);
+
+        assert_eq!(score_picks(&corrects, false, &["5", "3", "2"]), 1.0);
+        assert_eq!(score_picks(&corrects, false, &["2", "3"]), 0.0);
+        assert_eq!(score_picks(&corrects, false, &["2", "3", "5", "4"]), 0.0);
+
+        assert_eq!(score_picks(&corrects, true, &["2", "3", "5"]), 1.0);
+        assert!((score_picks(&corrects, true, &["2", "3"]) - 2.0 / 3.0).abs() < 1e-9);
+        assert!((score_picks(&corrects, true, &["2", "3", "4"]) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(score_picks(&corrects, true, &["4", "6"]), 0.0);
+    }
+
+    #[test]
+    fn free_text_accepts_listed_answers_or_pattern() {
+        let kind = QuestionKind::FreeText {
+            accepted: strings(&["Paris"]),
+            pattern: Some(r"(the )?city of light".to_string()),
+        };
+
+        assert_eq!(kind.score_text("  paris "), 1.0);
+        assert_eq!(kind.score_text("The  City of Light"), 1.0);
+        assert_eq!(kind.score_text("city of lights"), 0.0);
+        assert_eq!(kind.score_text("Lyon"), 0.0);
+    }
+
+    #[test]
+    fn numeric_allows_tolerance() {
+        let kind = QuestionKind::Numeric {
+            answer: 0.33,
+            tolerance: 0.01,
+        };
+
+        assert_eq!(kind.score_text("0.34"), 1.0);
+        assert_eq!(kind.score_text(" 0.32 "), 1.0);
+        assert_eq!(kind.score_text("0.4"), 0.0);
+        assert_eq!(kind.score_text("pi"), 0.0);
+    }
+
+    #[test]
+    fn problems_are_reported() {
+        let invalid_pattern = QuestionKind::FreeText {
+            accepted: Vec::new(),
+            pattern: Some("(".to_string()),
+        };
+        let negative_tolerance = QuestionKind::Numeric {
+            answer: 1.0,
+            tolerance: -1.0,
+        };
+        let no_corrects = QuestionKind::MultiSelect {
+            corrects: Vec::new(),
+            incorrects: strings(&["no"]),
+            partial: false,
+        };
+
+        assert_eq!(
+            invalid_pattern.problem().unwrap().to_string(),
+            "the pattern is not a valid regular expression"
+        );
+        assert_eq!(
+            negative_tolerance.problem().unwrap().to_string(),
+            "the tolerance cannot be negative"
+        );
+        assert_eq!(
+            no_corrects.problem().unwrap().to_string(),
+            "at least one correct answer is required"
+        );
+    }
+
+    #[test]
+    fn kinds_are_tagged_in_json() {
+        let kind: QuestionKind = serde_json::from_str(r#"{"kind":"numeric","answer":42}"#).unwrap();
+
+        assert_eq!(
+            kind,
+            QuestionKind::Numeric {
+                answer: 42.0,
+                tolerance: 0.0
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&kind).unwrap(),
+            r#"{"kind":"numeric","answer":42.0}"#
+        );
+    }
+}
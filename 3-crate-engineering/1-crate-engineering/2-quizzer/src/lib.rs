@@ -1,5 +1,25 @@
 pub mod add;
+pub mod check;
+pub mod convert;
+pub mod edit;
+pub mod error;
 pub mod fake_interface;
+pub mod file_store;
+pub mod grade;
+pub mod i18n;
 pub mod interface;
+pub mod kind;
+pub mod list;
+pub mod memory_store;
+pub mod players;
 pub mod quiz;
 pub mod real_interface;
+pub mod results;
+pub mod schema;
+pub mod seal;
+pub mod serve;
+pub mod sqlite_store;
+pub mod stats;
+pub mod store;
+pub mod study;
+pub mod tui_interface;
@@ -0,0 +1,174 @@
+use crate::{
+    error::{QuizError, check_io},
+    interface::Interface,
+    kind::QuestionKind,
+    quiz::load_quiz,
+    store::QuizStore,
+};
+
+/// Lists the questions of the quiz in `quiz_file`, numbered from 1, with the
+/// correct answers marked.
+pub fn list_questions(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+) -> Result<(), QuizError> {
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    if quiz.is_empty() {
+        let msg = inter.messages().get("no-questions");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    for (idx, q) in quiz.questions.iter().enumerate() {
+        inter.write_stdout_line(&format!("{}. {}", idx + 1, q.text));
+        match &q.kind {
+            QuestionKind::MultipleChoice {
+                correct,
+                incorrects,
+            } => {
+                inter.write_stdout_line(&format!("   [x] {}", correct));
+                for ans in incorrects {
+                    inter.write_stdout_line(&format!("   [ ] {}", ans));
+                }
+            }
+            QuestionKind::MultiSelect {
+                corrects,
+                incorrects,
+                ..
+            } => {
+                for ans in corrects {
+                    inter.write_stdout_line(&format!("   [x] {}", ans));
+                }
+                for ans in incorrects {
+                    inter.write_stdout_line(&format!("   [ ] {}", ans));
+                }
+            }
+            QuestionKind::Sealed { choices, .. } if !choices.is_empty() => {
+                for ans in choices {
+                    inter.write_stdout_line(&format!("   [?] {}", ans));
+                }
+            }
+            kind @ (QuestionKind::FreeText { .. }
+            | QuestionKind::Numeric { .. }
+            | QuestionKind::Sealed { .. }) => {
+                let msg = inter
+                    .messages()
+                    .format("list-answer", &[("answer", kind.expected().into())]);
+                inter.write_stdout_line(&format!("   {}", msg));
+            }
+        }
+    }
+    check_io(inter)
+}
+
+/// Lists the quizzes found in `dir` along with how many questions each has.
+pub fn list_quizzes(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    dir: &str,
+) -> Result<(), QuizError> {
+    let quiz_files = store
+        .list_quiz_files(dir)
+        .map_err(|source| QuizError::Load {
+            file: dir.to_string(),
+            source,
+        })?;
+
+    if quiz_files.is_empty() {
+        let msg = inter
+            .messages()
+            .format("list-no-quizzes", &[("dir", dir.into())]);
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    for quiz_file in quiz_files {
+        let file = quiz_file.as_str().into();
+        let msg = match load_quiz(store, &quiz_file) {
+            Ok(quiz) => inter
+                .messages()
+                .format("list-quiz", &[("file", file), ("count", quiz.len().into())]),
+            Err(_) => inter
+                .messages()
+                .format("list-invalid-quiz", &[("file", file)]),
+        };
+        inter.write_stdout_line(&msg);
+    }
+    check_io(inter)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+
+    use super::*;
+
+    #[test]
+    fn lists_numbered_questions() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[
+                {"text":"What's my name?","correct":"quizzer","incorrects":["andy","sam"]},
+                {"text":"True?","correct":"yes","incorrects":["no"]}
+            ]}"#,
+        );
+
+        list_questions(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            1. What's my name?\n   \
+               [x] quizzer\n   \
+               [ ] andy\n   \
+               [ ] sam\n\
+            2. True?\n   \
+               [x] yes\n   \
+               [ ] no\n\
+            "
+        );
+    }
+
+    #[test]
+    fn lists_quizzes_in_directory() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new()
+            .with_file(
+                "banks/ownership.json",
+                r#"{"questions":[{"text":"Who owns it?","correct":"me","incorrects":["you"]}]}"#,
+            )
+            .with_file("banks/traits.json", r#"{"questions":[]}"#)
+            .with_file("banks/notes.json", "not json")
+            .with_file("banks/nested/other.json", r#"{"questions":[]}"#)
+            .with_file("quiz.json", r#"{"questions":[]}"#);
+
+        list_quizzes(&mut inter, &mut store, "banks").unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            banks/notes.json (not a valid quiz)\n\
+            banks/ownership.json (1 questions)\n\
+            banks/traits.json (0 questions)\n\
+            "
+        );
+    }
+
+    #[test]
+    fn says_when_no_quizzes_found() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        list_quizzes(&mut inter, &mut store, ".").unwrap();
+
+        assert_eq!(inter.stdout(), "No quizzes found in '.'.\n");
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use crate::quiz::DEFAULT_QUIZ_FILE;
+use crate::store::{QuizStore, quiz_files_in};
+
+/// Keeps quizzes, and the other files read and written through the store, in
+/// memory, for tests.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// Quizzes, and the files set up by tests, by path.
+    files: BTreeMap<String, String>,
+    /// Files written with [`QuizStore::write_file`], such as results, by
+    /// path. They are kept apart so that they are not listed as quizzes.
+    written: BTreeMap<String, String>,
+    /// Files saved by another session, each just before the quiz at that
+    /// path is next updated.
+    changes: BTreeMap<String, String>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the contents of the default quiz file, as if it had been saved before.
+    pub fn with_quiz_file(self, json: &str) -> Self {
+        self.with_file(DEFAULT_QUIZ_FILE, json)
+    }
+
+    /// Sets the contents of the file at `path`, as if it had been saved before.
+    pub fn with_file(mut self, path: &str, json: &str) -> Self {
+        self.files.insert(path.to_string(), json.to_string());
+        self
+    }
+
+    /// Replaces the contents of the file at `path` just before it is next
+    /// updated, as if another session had saved it after it was loaded.
+    pub fn with_change_before_update(mut self, path: &str, json: &str) -> Self {
+        self.changes.insert(path.to_string(), json.to_string());
+        self
+    }
+
+    pub fn quiz_file(&self) -> String {
+        self.saved_quiz_file()
+            .expect("MemoryStore.quiz_file: No json was saved.")
+            .to_string()
+    }
+
+    /// Returns the default quiz file contents, if any were saved.
+    pub fn saved_quiz_file(&self) -> Option<&str> {
+        self.file(DEFAULT_QUIZ_FILE)
+    }
+
+    /// Returns the contents of the file at `path`, if any were saved.
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.written
+            .get(path)
+            .or_else(|| self.files.get(path))
+            .map(String::as_str)
+    }
+}
+
+impl QuizStore for MemoryStore {
+    fn read_quiz_json(&mut self, quiz: &str) -> anyhow::Result<String> {
+        Ok(self.files.get(quiz).cloned().unwrap_or_default())
+    }
+
+    fn write_quiz_json(&mut self, quiz: &str, text: &str) -> anyhow::Result<()> {
+        self.files.insert(quiz.to_string(), text.to_string());
+        Ok(())
+    }
+
+    fn update_quiz_json(
+        &mut self,
+        quiz: &str,
+        update: &mut dyn FnMut(&str) -> anyhow::Result<String>,
+    ) -> anyhow::Result<()> {
+        if let Some(json) = self.changes.remove(quiz) {
+            self.files.insert(quiz.to_string(), json);
+        }
+        let text = update(self.files.get(quiz).map_or("", String::as_str))?;
+        self.files.insert(quiz.to_string(), text);
+        Ok(())
+    }
+
+    /// Lists the `.json` files directly inside `dir`.
+    fn list_quiz_files(&mut self, dir: &str) -> anyhow::Result<Vec<String>> {
+        Ok(quiz_files_in(dir, self.files.keys().map(String::as_str)))
+    }
+
+    fn read_file(&mut self, path: &str) -> anyhow::Result<String> {
+        match self.file(path) {
+            Some(contents) => Ok(contents.to_string()),
+            None => Err(anyhow::anyhow!("No such file")),
+        }
+    }
+
+    fn write_file(&mut self, path: &str, text: &str) -> anyhow::Result<()> {
+        self.written.insert(path.to_string(), text.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+//! Hot-seat play, where several players share the terminal.
+//!
+//! Players either take turns, each answering their own questions, or share
+//! every question, which is passed from player to player until someone gets
+//! it right and wins the point.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::{
+    interface::Interface,
+    quiz::{
+        Outcome, Question, ask_question, fmt_points, give_feedback, read_answer, shown_choices,
+    },
+};
+
+/// A player and how they are doing.
+struct Player<'a> {
+    name: &'a str,
+    score: f64,
+    /// The number of questions the player answered themselves.
+    asked: usize,
+}
+
+/// Asks `questions` of `players` in turn and prints a leaderboard. With
+/// `first_correct`, every question is shared and only the first right answer
+/// scores, with the player who goes first moving round each question.
+pub(crate) fn play(
+    inter: &mut dyn Interface,
+    questions: &[&Question],
+    players: &[String],
+    first_correct: bool,
+    timeout: Option<Duration>,
+) {
+    let names: Vec<&str> = players
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if names.is_empty() {
+        let msg = inter.messages().get("players-none");
+        inter.write_stdout_line(&msg);
+        return;
+    }
+    if names.iter().collect::<BTreeSet<_>>().len() < names.len() {
+        let msg = inter.messages().get("players-same-name");
+        inter.write_stdout_line(&msg);
+        return;
+    }
+
+    let mut players: Vec<Player> = names
+        .into_iter()
+        .map(|name| Player {
+            name,
+            score: 0.0,
+            asked: 0,
+        })
+        .collect();
+
+    let answered = if first_correct {
+        play_shared(inter, questions, &mut players, timeout)
+    } else {
+        play_in_turn(inter, questions, &mut players, timeout)
+    };
+    if answered < questions.len() {
+        let msg = inter.messages().format(
+            "players-input-ended",
+            &[
+                ("answered", answered.into()),
+                ("total", questions.len().into()),
+            ],
+        );
+        inter.write_stdout_line(&format!("\n{}\n", msg));
+    }
+
+    write_leaderboard(inter, &players, first_correct);
+}
+
+/// Gives each question to the next player in turn. Returns how many
+/// questions were answered before the input ended.
+fn play_in_turn(
+    inter: &mut dyn Interface,
+    questions: &[&Question],
+    players: &mut [Player],
+    timeout: Option<Duration>,
+) -> usize {
+    for (idx, q) in questions.iter().enumerate() {
+        let player = &mut players[idx % players.len()];
+        inter.show_progress(idx, questions.len(), player.score);
+        write_turn(inter, player.name);
+
+        let Some(answer) = ask_question(inter, q, timeout) else {
+            return idx;
+        };
+        player.score += answer.points;
+        player.asked += 1;
+    }
+    questions.len()
+}
+
+/// Passes each question from player to player until one of them answers it
+/// right. Returns how many questions were settled before the input ended.
+fn play_shared(
+    inter: &mut dyn Interface,
+    questions: &[&Question],
+    players: &mut [Player],
+    timeout: Option<Duration>,
+) -> usize {
+    for (idx, q) in questions.iter().enumerate() {
+        // Everyone sees the same choices, in the same order.
+        let (choices, correct_label) = shown_choices(&q.kind, inter.rng());
+        let msg = inter.messages().format(
+            "players-question",
+            &[
+                ("number", (idx + 1).into()),
+                ("total", questions.len().into()),
+            ],
+        );
+        inter.write_stdout_line(&msg);
+
+        let mut won = false;
+        for turn in 0..players.len() {
+            let player = &mut players[(idx + turn) % players.len()];
+            inter.show_progress(idx, questions.len(), player.score);
+            write_turn(inter, player.name);
+
+            let Some(answer) = read_answer(inter, q, &choices, &correct_label, timeout) else {
+                return idx;
+            };
+            if answer.outcome == Outcome::Correct {
+                // The explanation would give the answer away, so it is only
+                // shown once the question is settled.
+                let notes = q.notes(inter.messages());
+                give_feedback(inter, &answer, notes);
+                player.score += 1.0;
+                won = true;
+                break;
+            }
+            give_feedback(inter, &answer, None);
+        }
+
+        if !won {
+            let messages = inter.messages();
+            let msg = messages.format("players-nobody", &[("answer", q.kind.expected().into())]);
+            let notes = q.notes(messages);
+            for line in std::iter::once(&msg).chain(&notes) {
+                inter.write_stdout_line(line);
+            }
+            inter.write_stdout_line("");
+        }
+    }
+    questions.len()
+}
+
+fn write_turn(inter: &mut dyn Interface, name: &str) {
+    let msg = inter
+        .messages()
+        .format("players-turn", &[("name", name.into())]);
+    inter.write_stdout_line(&msg);
+}
+
+/// Prints the players from the highest score to the lowest, and who won.
+/// Players with the same score share a place.
+fn write_leaderboard(inter: &mut dyn Interface, players: &[Player], first_correct: bool) {
+    let mut ranked: Vec<&Player> = players.iter().collect();
+    // Stable, so players with the same score stay in the order they played.
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let msg = inter.messages().get("players-leaderboard");
+    inter.write_stdout_line(&msg);
+    for player in &ranked {
+        let place = 1 + ranked.iter().filter(|p| p.score > player.score).count();
+        let points = fmt_points(player.score).into();
+        let score = if first_correct {
+            inter
+                .messages()
+                .format("players-points", &[("points", points)])
+        } else {
+            inter.messages().format(
+                "players-points-of",
+                &[("points", points), ("total", player.asked.into())],
+            )
+        };
+        inter.write_stdout_line(&format!("  {}. {}: {}", place, player.name, score));
+    }
+
+    let best: Vec<&str> = ranked
+        .iter()
+        .filter(|p| p.score == ranked[0].score)
+        .map(|p| p.name)
+        .collect();
+    let msg = match best.as_slice() {
+        [winner] => inter
+            .messages()
+            .format("players-wins", &[("name", (*winner).into())]),
+        [rest @ .., last] => inter.messages().format(
+            "players-tie",
+            &[("names", rest.join(", ").into()), ("last", (*last).into())],
+        ),
+        [] => return,
+    };
+    inter.write_stdout_line(&format!("\n{}", msg));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_interface::FakeInterface;
+    use crate::i18n::Lang;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, QuizOptions, run_quiz};
+
+    const QUIZ: &str = r#"{"version":2,"questions":[
+        {"text":"2 + 2?","kind":"numeric","answer":4},
+        {"text":"Capital of France?","kind":"free_text","accepted":["Paris"],
+         "explanation":"It has been since 987."},
+        {"text":"3 * 3?","kind":"numeric","answer":9}
+    ]}"#;
+
+    fn options(first_correct: bool) -> QuizOptions {
+        QuizOptions {
+            players: vec!["alice".to_string(), " bob".to_string()],
+            first_correct,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn players_take_turns_and_score_their_own_questions() {
+        let mut inter = FakeInterface::with_stdin("4\nLyon\n9\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options(false)).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            alice's turn:\n\
+            2 + 2?\n>>> Correct!\n\n\
+            bob's turn:\n\
+            Capital of France?\n>>> Wrong answer.\n\
+            Explanation: It has been since 987.\n\n\
+            alice's turn:\n\
+            3 * 3?\n>>> Correct!\n\n\
+            Leaderboard:\n  \
+              1. alice: 2 out of 2 points\n  \
+              2. bob: 0 out of 1 points\n\
+            \n\
+            alice wins!\n\
+            "
+        );
+    }
+
+    #[test]
+    fn first_right_answer_wins_shared_questions() {
+        let mut inter = FakeInterface::with_stdin("5\n4\nLyon\nParis\n8\n10\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options(true)).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Question 1 of 3, the first right answer wins the point.\n\
+            alice's turn:\n\
+            2 + 2?\n>>> Wrong answer.\n\n\
+            bob's turn:\n\
+            2 + 2?\n>>> Correct!\n\n\
+            Question 2 of 3, the first right answer wins the point.\n\
+            bob's turn:\n\
+            Capital of France?\n>>> Wrong answer.\n\n\
+            alice's turn:\n\
+            Capital of France?\n>>> Correct!\n\
+            Explanation: It has been since 987.\n\n\
+            Question 3 of 3, the first right answer wins the point.\n\
+            alice's turn:\n\
+            3 * 3?\n>>> Wrong answer.\n\n\
+            bob's turn:\n\
+            3 * 3?\n>>> Wrong answer.\n\n\
+            Nobody got it right. The answer was: 9\n\
+            \n\
+            Leaderboard:\n  \
+              1. alice: 1 points\n  \
+              1. bob: 1 points\n\
+            \n\
+            It's a tie between alice and bob!\n\
+            "
+        );
+    }
+
+    #[test]
+    fn leaderboard_is_printed_when_input_ends() {
+        let mut inter = FakeInterface::with_stdin("4\n");
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options(false)).unwrap();
+
+        assert!(inter.stdout().ends_with(
+            "\
+            Input ended after 1 of 3 questions.\n\
+            \n\
+            Leaderboard:\n  \
+              1. alice: 1 out of 1 points\n  \
+              2. bob: 0 out of 0 points\n\
+            \n\
+            alice wins!\n\
+            "
+        ));
+    }
+
+    #[test]
+    fn players_need_different_names() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+        let options = QuizOptions {
+            players: vec!["alice".to_string(), "alice ".to_string()],
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(inter.stdout(), "Every player needs a different name.\n");
+    }
+
+    #[test]
+    fn players_are_told_their_turn_in_dutch() {
+        let mut inter = FakeInterface::with_stdin("4\nLyon\n9\n").with_lang(Lang::Nl);
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options(false)).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            alice is aan de beurt:\n\
+            2 + 2?\n>>> Goed!\n\n\
+            bob is aan de beurt:\n\
+            Capital of France?\n>>> Fout antwoord.\n\
+            Uitleg: It has been since 987.\n\n\
+            alice is aan de beurt:\n\
+            3 * 3?\n>>> Goed!\n\n\
+            Ranglijst:\n  \
+              1. alice: 2 van de 2 punten\n  \
+              2. bob: 0 van de 1 punten\n\
+            \n\
+            alice wint!\n\
+            "
+        );
+    }
+}
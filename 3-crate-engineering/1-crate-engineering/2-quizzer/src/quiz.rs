@@ -1,10 +1,30 @@
-use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
 
 use serde::{Deserialize, Serialize};
 
-use crate::interface::Interface;
+use crate::check::warn_problems;
+use crate::error::{QuizError, check_io};
+use crate::i18n::Messages;
+use crate::interface::{Interface, Prompt, TimedLine};
+use crate::kind::{Kind, QuestionKind, split_picks};
+use crate::players::play;
+use crate::results::{QuestionResult, QuizResults, ResultsFormat, format_results};
+use crate::schema::{parse_quiz, serialize_quiz};
+use crate::seal::{Locked, unlock_quiz};
+use crate::stats::{Run, history_file, record_run};
+use crate::store::QuizStore;
+use crate::study::unix_secs;
+
+/// The quiz file used when none is given with `--file`.
+pub const DEFAULT_QUIZ_FILE: &str = "quiz.json";
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Quiz {
     pub questions: Vec<Question>,
 }
@@ -27,87 +47,1388 @@ impl Quiz {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Question {
     pub text: String,
-    pub correct: String,
-    pub incorrects: Vec<String>,
+    #[serde(flatten)]
+    pub kind: QuestionKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Why the correct answer is correct, shown after the question is answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// Where to read more, such as a URL or a chapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+impl Question {
+    /// Returns true if the question has `tag`, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Returns the explanation and reference as lines to show after the
+    /// question is answered, if it has either.
+    pub fn notes(&self, messages: &Messages) -> Option<String> {
+        let note = |id, text: &String| messages.format(id, &[("text", text.as_str().into())]);
+        let lines: Vec<String> = [
+            self.explanation
+                .as_ref()
+                .map(|e| note("quiz-explanation", e)),
+            self.reference.as_ref().map(|r| note("quiz-reference", r)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{}", name)
+    }
 }
 
-pub fn run_quiz(inter: &mut dyn Interface) {
-    let load_res = load_quiz(inter);
-    let quiz = match load_res {
-        Ok(q) => q,
-        Err(e) => {
-            inter.write_stdout_line(&format!("Failed to load quiz.json: {}", e));
-            return;
+impl FromStr for Difficulty {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(anyhow::anyhow!(
+                "unknown difficulty '{}', expected easy, medium or hard",
+                other
+            )),
+        }
+    }
+}
+
+/// Splits a comma separated list of tags, dropping empty ones.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Which of the quiz's questions to ask, and in what order.
+#[derive(Debug, Default)]
+pub struct QuizOptions {
+    /// Only ask questions with any of these tags. Asks all questions if empty.
+    pub tags: Vec<String>,
+    /// Only ask questions of this difficulty.
+    pub difficulty: Option<Difficulty>,
+    /// Ask at most this many questions.
+    pub limit: Option<usize>,
+    /// Ask the questions in a random order instead of the quiz's order.
+    pub shuffle: bool,
+    /// The time allowed for the whole quiz.
+    pub time_limit: Option<Duration>,
+    /// The time allowed for each question.
+    pub per_question_timeout: Option<Duration>,
+    /// Where to write a record of the run, for automatic grading.
+    pub results: Option<String>,
+    /// The format of the results, guessed from the file extension if not set.
+    pub results_format: Option<ResultsFormat>,
+    /// List the missed questions with their correct answers at the end.
+    pub review: bool,
+    /// The players taking turns at the quiz, if it is played by several.
+    pub players: Vec<String>,
+    /// Ask every player each question, and give the point to the first to
+    /// answer it right.
+    pub first_correct: bool,
+    /// The key a sealed quiz was sealed with, to check its answers.
+    pub key: Option<String>,
+}
+
+impl QuizOptions {
+    /// Returns true if the quiz is timed, in which case the time spent on
+    /// each question is reported at the end.
+    pub fn is_timed(&self) -> bool {
+        self.time_limit.is_some() || self.per_question_timeout.is_some()
+    }
+
+    /// Selects the questions to ask, shuffling them with `rng` if asked to.
+    pub fn select<'a>(
+        &self,
+        questions: &'a [Question],
+        rng: &mut dyn RngCore,
+    ) -> Vec<&'a Question> {
+        let mut selected: Vec<&Question> = questions
+            .iter()
+            .filter(|q| self.tags.is_empty() || self.tags.iter().any(|t| q.has_tag(t)))
+            .filter(|q| self.difficulty.is_none() || q.difficulty == self.difficulty)
+            .collect();
+
+        if self.shuffle {
+            selected.shuffle(rng);
+        }
+        if let Some(limit) = self.limit {
+            selected.truncate(limit);
+        }
+
+        selected
+    }
+}
+
+/// Asks the questions of the quiz in `quiz_file` and reports the score.
+///
+/// If the input ends before every question is answered, the score so far is
+/// reported with the remaining questions counting as wrong.
+pub fn run_quiz(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    options: &QuizOptions,
+) -> Result<(), QuizError> {
+    let mut quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    // Without its key, a quiz sealed with one can still be taken to record the
+    // answers in the results, for `grade --key` to score later.
+    let scored = match unlock_quiz(&mut quiz, options.key.as_deref()) {
+        Ok(()) => true,
+        Err(Locked::NoKey) if options.results.is_some() && options.players.is_empty() => false,
+        Err(reason) => {
+            return Err(QuizError::Sealed {
+                file: quiz_file.to_string(),
+                reason,
+            });
         }
     };
 
     if quiz.is_empty() {
-        inter.write_stdout_line("There are no quiz questions.\nFirst add some with ./quizzer add");
-        return;
+        let msg = inter.messages().get("no-questions");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
     }
+    warn_problems(inter, quiz_file, &quiz);
 
-    let mut score: u32 = 0;
+    let questions = options.select(&quiz.questions, inter.rng());
+    if questions.is_empty() {
+        let msg = inter.messages().get("quiz-no-match");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    if !options.players.is_empty() {
+        play(
+            inter,
+            &questions,
+            &options.players,
+            options.first_correct,
+            options.per_question_timeout,
+        );
+        return check_io(inter);
+    }
 
-    for q in quiz.questions.iter() {
-        inter.write_stdout(&q.text);
+    if let (false, Some(path)) = (scored, &options.results) {
+        let msg = inter
+            .messages()
+            .format("quiz-unscored", &[("file", path.as_str().into())]);
+        inter.write_stdout_line(&format!("{}\n", msg));
+    }
 
-        inter.write_stdout_line(&fmt_ans_choices(&q.correct, &q.incorrects));
-        inter.write_stdout(">>> ");
+    let mut score = 0.0;
+    // How each question was answered and how long it took, if it was asked.
+    let mut results: Vec<Option<(Answer, Duration)>> = Vec::with_capacity(questions.len());
+    let started = inter.now();
+    let mut input_ended = false;
 
-        let ans = inter.read_stdin_line().unwrap();
+    for q in questions.iter() {
+        // The whole quiz's time limit can cut a question's time short.
+        let remaining = options
+            .time_limit
+            .map(|limit| limit.saturating_sub(elapsed(started, inter.now())));
+        if remaining == Some(Duration::ZERO) {
+            break;
+        }
+        let timeout = match (remaining, options.per_question_timeout) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
 
-        if ans == q.correct {
-            score += 1;
-            inter.write_stdout_line("Correct!\n");
+        let asked = inter.now();
+        let answer = if scored {
+            inter.show_progress(results.len(), questions.len(), score);
+            ask_question(inter, q, timeout)
         } else {
-            inter.write_stdout_line("Wrong answer.\n");
+            record_answer(inter, q, timeout)
+        };
+        let Some(answer) = answer else {
+            input_ended = true;
+            break;
+        };
+        score += answer.points;
+        results.push(Some((answer, elapsed(asked, inter.now()))));
+    }
+
+    let unanswered = questions.len() - results.len();
+    if input_ended {
+        let msg = inter.messages().format(
+            "quiz-input-ended",
+            &[
+                ("answered", results.len().into()),
+                ("total", questions.len().into()),
+                ("remaining", unanswered.into()),
+            ],
+        );
+        inter.write_stdout_line(&format!("\n{}\n", msg));
+    } else if unanswered > 0 {
+        let msg = inter
+            .messages()
+            .format("quiz-out-of-time", &[("remaining", unanswered.into())]);
+        inter.write_stdout_line(&format!("{}\n", msg));
+    }
+    results.resize(questions.len(), None);
+
+    if options.is_timed() {
+        let msg = inter.messages().get("quiz-times");
+        inter.write_stdout_line(&msg);
+        for (idx, (q, result)) in questions.iter().zip(&results).enumerate() {
+            let time = match result {
+                Some((answer, took)) if answer.outcome == Outcome::TimedOut => {
+                    let seconds = format!("{:.1}", took.as_secs_f64());
+                    inter
+                        .messages()
+                        .format("quiz-timed-out", &[("seconds", seconds.into())])
+                }
+                Some((_, took)) => format!("{:.1}s", took.as_secs_f64()),
+                None => inter.messages().get("quiz-not-answered"),
+            };
+            inter.write_stdout_line(&format!("  {}. {}: {}", idx + 1, q.text, time));
+        }
+        inter.write_stdout_line("");
+    }
+
+    if scored {
+        let msg = inter.messages().format(
+            "quiz-score",
+            &[
+                ("score", fmt_points(score).into()),
+                ("total", questions.len().into()),
+            ],
+        );
+        inter.write_stdout_line(&msg);
+    }
+
+    let results = QuizResults {
+        quiz: quiz_file.to_string(),
+        unscored: !scored,
+        score,
+        total: questions.len(),
+        questions: questions
+            .iter()
+            .zip(results)
+            .map(|(q, result)| question_result(q, result))
+            .collect(),
+    };
+    // An unscored run has nothing to review and would only skew the history.
+    if scored {
+        if options.review {
+            write_review(inter, &questions, &results.questions);
+        }
+
+        let run = Run::new(unix_secs(started), &results);
+        if let Err(e) = record_run(store, quiz_file, &run) {
+            let msg = inter.messages().format(
+                "quiz-save-failed",
+                &[
+                    ("file", history_file(quiz_file).into()),
+                    ("error", e.to_string().into()),
+                ],
+            );
+            inter.write_stdout_line(&msg);
+        }
+    }
+
+    if let Some(path) = &options.results {
+        let format = options
+            .results_format
+            .unwrap_or_else(|| ResultsFormat::from_path(path));
+        format_results(format, &results)
+            .and_then(|text| store.write_file(path, &text))
+            .map_err(|source| QuizError::Results {
+                file: path.clone(),
+                source,
+            })?;
+        if !scored {
+            let msg = inter
+                .messages()
+                .format("quiz-recorded", &[("file", path.as_str().into())]);
+            inter.write_stdout_line(&msg);
         }
     }
 
-    inter.write_stdout_line(&format!("You scored {score} out of {} points.", quiz.len()));
+    check_io(inter)
 }
 
-/// Displays the answers choices in a random order.
-fn fmt_ans_choices(correct: &str, incorrects: &Vec<String>) -> String {
-    let mut output = String::new();
+/// Lists the questions that were not answered fully right, with the answer
+/// given, the correct answer and any explanation.
+fn write_review(inter: &mut dyn Interface, questions: &[&Question], results: &[QuestionResult]) {
+    let missed: Vec<_> = questions
+        .iter()
+        .zip(results)
+        .filter(|(_, result)| !result.correct)
+        .collect();
+    if missed.is_empty() {
+        let msg = inter.messages().get("quiz-nothing-to-review");
+        inter.write_stdout_line(&format!("\n{}", msg));
+        return;
+    }
+
+    let msg = inter.messages().get("quiz-review");
+    inter.write_stdout_line(&format!("\n{}", msg));
+    for (q, result) in missed {
+        let messages = inter.messages();
+        let given = match &result.answer {
+            Some(answer) => answer.clone(),
+            None if result.timed_out => messages.get("quiz-no-answer-in-time"),
+            None => messages.get("quiz-no-answer"),
+        };
+        let lines = [
+            format!("\n{}", q.text),
+            messages.format("quiz-your-answer", &[("answer", given.into())]),
+            messages.format(
+                "quiz-correct-answer",
+                &[("answer", result.correct_answer.as_str().into())],
+            ),
+        ];
+        let notes = q.notes(messages);
+        for line in lines.iter().chain(&notes) {
+            inter.write_stdout_line(line);
+        }
+    }
+}
+
+/// Records how `q` was answered, if it was asked.
+fn question_result(q: &Question, result: Option<(Answer, Duration)>) -> QuestionResult {
+    let (answer, seconds) = match result {
+        Some((answer, took)) => (Some(answer), Some(took.as_secs_f64())),
+        None => (None, None),
+    };
+    let outcome = answer.as_ref().map(|a| a.outcome);
+    QuestionResult {
+        question: q.text.clone(),
+        correct_answer: q.kind.expected(),
+        points: answer.as_ref().map_or(0.0, |a| a.points),
+        answer: answer.and_then(|a| a.given),
+        correct: outcome == Some(Outcome::Correct),
+        timed_out: outcome == Some(Outcome::TimedOut),
+        seconds,
+    }
+}
+
+/// How a question was answered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    /// Some, but not all, of a multi-select question's answers were picked.
+    Partial,
+    Wrong,
+    /// No answer was given in time, which counts as wrong.
+    TimedOut,
+}
+
+/// The answer given to a question.
+#[derive(Clone, Debug)]
+pub(crate) struct Answer {
+    pub outcome: Outcome,
+    /// The points scored, from 0 to 1.
+    pub points: f64,
+    /// What the user entered, or `None` if the question timed out.
+    pub given: Option<String>,
+}
+
+/// Asks a single question and tells the user whether they got it right,
+/// giving up after `timeout` if there is one.
+/// Returns how the question was answered, or `None` if there is no more input.
+pub(crate) fn ask_question(
+    inter: &mut dyn Interface,
+    q: &Question,
+    timeout: Option<Duration>,
+) -> Option<Answer> {
+    let (choices, correct_label) = shown_choices(&q.kind, inter.rng());
+    let answer = read_answer(inter, q, &choices, &correct_label, timeout)?;
+    let notes = q.notes(inter.messages());
+    give_feedback(inter, &answer, notes);
+    Some(answer)
+}
 
-    let show_correct = rand::rng().random_range(0..3);
+/// Asks a question whose answer cannot be checked without the quiz's key and
+/// records the text of the answer, only telling the user if the time is up.
+/// Returns `None` if there is no more input.
+fn record_answer(
+    inter: &mut dyn Interface,
+    q: &Question,
+    timeout: Option<Duration>,
+) -> Option<Answer> {
+    let (choices, _) = shown_choices(&q.kind, inter.rng());
+    let answer = read_answer(inter, q, &choices, "", timeout)?;
+    if answer.outcome == Outcome::TimedOut {
+        give_feedback(inter, &answer, None);
+        return Some(answer);
+    }
+    // The labels are shuffled on every run, so the picks are kept by text.
+    let given = answer
+        .given
+        .map(|ans| picks(&q.kind, &choices, &ans).join(", "));
+    Some(Answer {
+        outcome: Outcome::Wrong,
+        points: 0.0,
+        given,
+    })
+}
+
+/// Asks a question shown with `choices` and scores the answer, without
+/// telling the user how they did, giving up after `timeout` if there is one.
+/// Returns `None` if there is no more input.
+pub(crate) fn read_answer(
+    inter: &mut dyn Interface,
+    q: &Question,
+    choices: &[&str],
+    correct_label: &str,
+    timeout: Option<Duration>,
+) -> Option<Answer> {
+    let prompt = Prompt {
+        text: &q.text,
+        choices,
+        multi: q.kind.kind() == Kind::MultiSelect,
+    };
 
-    for (idx, ans) in incorrects.iter().enumerate() {
-        if idx == show_correct {
-            output.push_str(&format!("\n -> {}", correct));
+    let line = match inter.ask(&prompt, timeout) {
+        Some(line) => line,
+        None => {
+            write_prompt(inter, &prompt);
+            match timeout {
+                None => inter
+                    .read_stdin_line()
+                    .map_or(TimedLine::Closed, TimedLine::Line),
+                Some(timeout) => inter.read_stdin_line_timeout(timeout),
+            }
+        }
+    };
+    let ans = match line {
+        TimedLine::Line(ans) => ans,
+        TimedLine::TimedOut => {
+            return Some(Answer {
+                outcome: Outcome::TimedOut,
+                points: 0.0,
+                given: None,
+            });
         }
-        output.push_str("\n -> ");
-        output.push_str(ans);
+        TimedLine::Closed => return None,
+    };
+
+    let points = score_answer(&q.kind, choices, correct_label, &ans);
+    let outcome = if points >= 1.0 {
+        Outcome::Correct
+    } else if points > 0.0 {
+        Outcome::Partial
+    } else {
+        Outcome::Wrong
+    };
+    Some(Answer {
+        outcome,
+        points,
+        given: Some(ans.trim().to_string()),
+    })
+}
+
+/// Tells the user how they answered, followed by `notes` such as the
+/// question's explanation and reference, if there are any.
+pub(crate) fn give_feedback(inter: &mut dyn Interface, answer: &Answer, notes: Option<String>) {
+    let messages = inter.messages();
+    let message = match answer.outcome {
+        Outcome::Correct => messages.get("quiz-correct"),
+        Outcome::Partial => messages.format(
+            "quiz-partly-correct",
+            &[("points", fmt_points(answer.points).into())],
+        ),
+        Outcome::Wrong => messages.get("quiz-wrong"),
+        Outcome::TimedOut => format!("\n{}", messages.get("quiz-time-up")),
+    };
+    match notes {
+        Some(notes) => {
+            inter.show_feedback(answer.outcome, &message);
+            inter.write_stdout_line(&format!("{}\n", notes));
+        }
+        None => inter.show_feedback(answer.outcome, &format!("{}\n", message)),
     }
+}
 
+/// Returns the choices of a question in the order to show them, labelled a,
+/// b, c, ..., and the label of the correct one for multiple choice questions.
+/// Questions whose answer is typed have no choices.
+pub(crate) fn shown_choices<'a>(
+    kind: &'a QuestionKind,
+    rng: &mut dyn RngCore,
+) -> (Vec<&'a str>, String) {
+    match kind {
+        QuestionKind::MultipleChoice {
+            correct,
+            incorrects,
+        } => place_ans_choices(rng, correct, incorrects),
+        QuestionKind::MultiSelect {
+            corrects,
+            incorrects,
+            ..
+        } => {
+            let mut choices: Vec<&str> = corrects
+                .iter()
+                .chain(incorrects)
+                .map(String::as_str)
+                .collect();
+            choices.shuffle(rng);
+            (choices, String::new())
+        }
+        // Which choices are correct is not known, so none is labelled correct.
+        QuestionKind::Sealed { choices, .. } => {
+            let mut choices: Vec<&str> = choices.iter().map(String::as_str).collect();
+            choices.shuffle(rng);
+            (choices, String::new())
+        }
+        QuestionKind::FreeText { .. } | QuestionKind::Numeric { .. } => (Vec::new(), String::new()),
+    }
+}
+
+/// Scores an answer to a question shown with `choices`, from 0 for wrong to 1
+/// for right. Choices can be answered with their label or their full text.
+pub(crate) fn score_answer(
+    kind: &QuestionKind,
+    choices: &[&str],
+    correct_label: &str,
+    ans: &str,
+) -> f64 {
+    match kind {
+        QuestionKind::MultipleChoice { correct, .. } => {
+            let right = match picked_choice(ans, choices) {
+                Some(idx) => choice_label(idx) == correct_label,
+                None => is_correct_answer(ans, correct_label, correct),
+            };
+            if right { 1.0 } else { 0.0 }
+        }
+        // Other questions with choices are scored by the text of the picks.
+        kind if !choices.is_empty() => kind.score_picks(&picks(kind, choices, ans)),
+        kind => kind.score_text(ans),
+    }
+}
+
+/// Returns the text of the choices an answer picks, each given by its label
+/// or text, or the answer itself for a question whose answer is typed.
+fn picks<'a>(kind: &QuestionKind, choices: &[&'a str], ans: &'a str) -> Vec<&'a str> {
+    if choices.is_empty() {
+        return vec![ans.trim()];
+    }
+    let picks: Vec<&str> = if kind.kind() == Kind::MultiSelect {
+        split_picks(ans).collect()
+    } else {
+        vec![ans]
+    };
+    picks
+        .into_iter()
+        .map(|pick| picked_choice(pick, choices).map_or(pick, |idx| choices[idx]))
+        .collect()
+}
+
+/// Formats a score, with at most two decimals.
+pub(crate) fn fmt_points(points: f64) -> String {
+    ((points * 100.0).round() / 100.0).to_string()
+}
+
+/// Returns the time from `since` to `now`, or zero if the clock went backwards.
+fn elapsed(since: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(since).unwrap_or_default()
+}
+
+/// Writes out a question and its labelled choices, for answering with a line.
+fn write_prompt(inter: &mut dyn Interface, prompt: &Prompt) {
+    inter.write_stdout(prompt.text);
+    inter.write_stdout(&fmt_choices(prompt.choices));
+    if prompt.multi {
+        let msg = inter.messages().get("quiz-pick-every");
+        inter.write_stdout(&format!("\n{}", msg));
+    }
+    inter.write_stdout_line("");
+    inter.write_stdout(">>> ");
+}
+
+/// Puts the answer choices in a random order, each to be shown with a label
+/// (a, b, c, ...) the user can answer with.
+///
+/// The correct answer is equally likely to be shown in any of the
+/// `incorrects.len() + 1` positions.
+/// Returns the choices and the label of the correct answer.
+fn place_ans_choices<'a>(
+    rng: &mut dyn RngCore,
+    correct: &'a str,
+    incorrects: &'a [String],
+) -> (Vec<&'a str>, String) {
+    let mut choices: Vec<&str> = incorrects.iter().map(String::as_str).collect();
+
+    let show_correct = rng.random_range(0..=incorrects.len());
+    choices.insert(show_correct, correct);
+
+    (choices, choice_label(show_correct))
+}
+
+/// Formats the answer choices, one per line, each with its label.
+fn fmt_choices(choices: &[&str]) -> String {
+    let mut output = String::new();
+    for (idx, ans) in choices.iter().enumerate() {
+        output.push_str(&format!("\n {}) {}", choice_label(idx), ans));
+    }
     output
 }
 
+/// Returns the label of the answer choice at `idx`: a letter for the first
+/// 26 choices, otherwise its 1-based number.
+pub fn choice_label(idx: usize) -> String {
+    match u8::try_from(idx) {
+        Ok(i) if i < 26 => char::from(b'a' + i).to_string(),
+        _ => (idx + 1).to_string(),
+    }
+}
+
+/// Finds the choice an answer picks, by the choice's text, its label or its
+/// number, 1 for the first choice. The text comes first, so "3" answers
+/// "2 + 1?" with the choice 3 and "b" picks the choice "b" wherever it is shown.
+fn picked_choice(ans: &str, choices: &[&str]) -> Option<usize> {
+    if let Some(idx) = choices.iter().position(|c| is_correct_text(ans, c)) {
+        return Some(idx);
+    }
+    let label = normalize_answer(ans);
+    (0..choices.len())
+        .find(|idx| choice_label(*idx) == label)
+        .or_else(|| {
+            label
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=choices.len()).contains(n))
+                .map(|n| n - 1)
+        })
+}
+
+/// Checks an answer given either as the label of the correct choice or as the
+/// full text of the correct answer, ignoring case and surrounding or repeated
+/// whitespace.
+fn is_correct_answer(ans: &str, correct_label: &str, correct: &str) -> bool {
+    normalize_answer(ans) == correct_label || is_correct_text(ans, correct)
+}
+
+/// Checks an answer given as the full text of the correct answer, ignoring
+/// case and surrounding or repeated whitespace.
+pub(crate) fn is_correct_text(ans: &str, correct: &str) -> bool {
+    normalize_answer(ans) == normalize_answer(correct)
+}
+
+pub(crate) fn normalize_answer(ans: &str) -> String {
+    ans.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 /// Loads the deserialized quiz if there is one, otherwise returns an empty quiz.
-pub fn load_quiz(inter: &mut dyn Interface) -> anyhow::Result<Quiz> {
-    let res = inter.read_quiz_json();
+pub fn load_quiz(store: &mut dyn QuizStore, quiz_file: &str) -> anyhow::Result<Quiz> {
+    let res = store.read_quiz_json(quiz_file);
     match res {
         Ok(contents) => {
             if contents.is_empty() {
                 Ok(Quiz::default())
             } else {
-                let deser_res = serde_json::from_str(&contents);
-                match deser_res {
-                    Ok(r) => Ok(r),
-                    Err(r) => Err(anyhow::anyhow!(r)),
-                }
+                Ok(parse_quiz(&contents)?)
             }
         }
         Err(e) => Err(e),
     }
 }
 
-pub fn save_quiz(inter: &mut dyn Interface, questions: &Quiz) -> anyhow::Result<()> {
-    let json = serde_json::to_string(questions)?;
-    inter.write_quiz_json(&json)?;
-    Ok(())
+/// Changes the quiz in `quiz_file` with `update` and saves it. The quiz is
+/// loaded afresh while the file is locked, so questions saved by another
+/// quizzer since it was last loaded are kept.
+pub fn update_quiz(
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    mut update: impl FnMut(&mut Quiz) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    store.update_quiz_json(quiz_file, &mut |contents| {
+        let mut quiz = if contents.is_empty() {
+            Quiz::default()
+        } else {
+            parse_quiz(contents)?
+        };
+        update(&mut quiz)?;
+        Ok(serialize_quiz(&quiz)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+
+    #[test]
+    fn ans_choices_include_every_answer_once() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for n in 0..6 {
+            let incorrects: Vec<String> = (0..n).map(|i| format!("wrong {i}")).collect();
+
+            let (choices, _) = place_ans_choices(&mut rng, "right", &incorrects);
+            let output = fmt_choices(&choices);
+
+            assert_eq!(output.lines().count(), n + 2);
+            assert_eq!(output.matches("right").count(), 1);
+            for ans in &incorrects {
+                assert!(output.contains(ans.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn correct_answer_can_be_shown_last() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let incorrects = vec!["false".to_string()];
+
+        let shown_last =
+            (0..100).any(|_| place_ans_choices(&mut rng, "true", &incorrects).1 == "b");
+
+        assert!(shown_last);
+    }
+
+    #[test]
+    fn correct_label_points_at_correct_answer() {
+        let incorrects = vec!["one".to_string(), "two".to_string(), "four".to_string()];
+
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let (choices, label) = place_ans_choices(&mut rng, "three", &incorrects);
+        let output = fmt_choices(&choices);
+
+        assert!(output.contains(&format!(" {label}) three")));
+    }
+
+    #[test]
+    fn answer_accepts_label_or_full_text() {
+        assert!(is_correct_answer("c", "c", "Ownership"));
+        assert!(is_correct_answer(" C ", "c", "Ownership"));
+        assert!(is_correct_answer("ownership", "c", "Ownership"));
+        assert!(is_correct_answer(
+            "  the   BORROW checker ",
+            "a",
+            "The borrow checker"
+        ));
+        assert!(!is_correct_answer("b", "c", "Ownership"));
+        assert!(!is_correct_answer("owner", "c", "Ownership"));
+    }
+
+    #[test]
+    fn choice_text_wins_over_label() {
+        let choices = ["b", "a", "c"];
+        let kind = QuestionKind::MultipleChoice {
+            correct: "a".to_string(),
+            incorrects: vec!["b".to_string(), "c".to_string()],
+        };
+
+        assert_eq!(score_answer(&kind, &choices, "b", "a"), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "b", "b"), 0.0);
+        assert_eq!(score_answer(&kind, &choices, "b", "2"), 1.0);
+
+        let kind = QuestionKind::MultiSelect {
+            corrects: vec!["a".to_string(), "c".to_string()],
+            incorrects: vec!["b".to_string()],
+            partial: false,
+        };
+        assert_eq!(score_answer(&kind, &choices, "", "a, c"), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "", "b, c"), 0.0);
+    }
+
+    #[test]
+    fn choices_can_be_picked_by_number() {
+        let choices = ["Andy", "Joe", "Sam"];
+        let kind = QuestionKind::MultipleChoice {
+            correct: "Joe".to_string(),
+            incorrects: vec!["Andy".to_string(), "Sam".to_string()],
+        };
+
+        assert_eq!(score_answer(&kind, &choices, "b", "b"), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "b", " 2 "), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "b", "1"), 0.0);
+        assert_eq!(score_answer(&kind, &choices, "b", "4"), 0.0);
+
+        let numbers = ["4", "3", "1"];
+        let sum = QuestionKind::MultipleChoice {
+            correct: "3".to_string(),
+            incorrects: vec!["4".to_string(), "1".to_string()],
+        };
+        assert_eq!(score_answer(&sum, &numbers, "b", "3"), 1.0);
+        assert_eq!(score_answer(&sum, &numbers, "b", "2"), 1.0);
+    }
+
+    #[test]
+    fn multi_select_accepts_choice_numbers() {
+        let choices = ["Rust", "Python", "C"];
+        let kind = QuestionKind::MultiSelect {
+            corrects: vec!["Rust".to_string(), "C".to_string()],
+            incorrects: vec!["Python".to_string()],
+            partial: false,
+        };
+
+        assert_eq!(score_answer(&kind, &choices, "", "1, 3"), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "", "a, 3"), 1.0);
+        assert_eq!(score_answer(&kind, &choices, "", "1, 2"), 0.0);
+    }
+
+    #[test]
+    fn quiz_accepts_full_answer() {
+        let mut inter = FakeInterface::with_stdin("  joe  \n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[{"text":"What's my name?","correct":"Joe","incorrects":["Andy","Sam"]}]}"#,
+        );
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert!(inter.stdout().contains("Correct!"));
+        assert!(inter.stdout().ends_with("You scored 1 out of 1 points.\n"));
+    }
+
+    #[test]
+    fn quiz_accepts_choice_label() {
+        let mut inter = FakeInterface::with_stdin("B\n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"questions":[{"text":"Is Rust fun?","correct":"Yes","incorrects":["No"]}]}"#,
+        );
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Is Rust fun?\n a) No\n b) Yes\n>>> Correct!\n\n\
+            You scored 1 out of 1 points.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn same_seed_shuffles_the_same_way() {
+        let quiz =
+            r#"{"questions":[{"text":"Pick one","correct":"1","incorrects":["2","3","4","5"]}]}"#;
+        let mut first = FakeInterface::with_stdin("a\n").with_seed(42);
+        let mut first_store = MemoryStore::new().with_quiz_file(quiz);
+        let mut second = FakeInterface::with_stdin("a\n").with_seed(42);
+        let mut second_store = MemoryStore::new().with_quiz_file(quiz);
+
+        run_quiz(
+            &mut first,
+            &mut first_store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+        run_quiz(
+            &mut second,
+            &mut second_store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(first.stdout(), second.stdout());
+    }
+
+    const TAGGED_QUIZ: &str = r#"{"version":1,"questions":[
+        {"text":"Who owns it?","correct":"me","incorrects":["no one"],"tags":["ownership"],"difficulty":"easy"},
+        {"text":"Can it move?","correct":"yes","incorrects":["no one"],"tags":["Ownership","moves"],"difficulty":"hard"},
+        {"text":"Is it a trait?","correct":"yes","incorrects":["no one"],"tags":["traits"]},
+        {"text":"Who borrows it?","correct":"you","incorrects":["no one"],"tags":["ownership"]}
+    ]}"#;
+
+    fn selected_texts(options: &QuizOptions) -> Vec<String> {
+        let quiz = crate::schema::parse_quiz(TAGGED_QUIZ).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        options
+            .select(&quiz.questions, &mut rng)
+            .iter()
+            .map(|q| q.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn select_filters_by_tag_ignoring_case() {
+        let options = QuizOptions {
+            tags: vec!["OWNERSHIP".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            selected_texts(&options),
+            vec!["Who owns it?", "Can it move?", "Who borrows it?"]
+        );
+    }
+
+    #[test]
+    fn select_filters_by_difficulty_and_limits() {
+        let hard = QuizOptions {
+            difficulty: Some(Difficulty::Hard),
+            ..Default::default()
+        };
+        let first_two = QuizOptions {
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(selected_texts(&hard), vec!["Can it move?"]);
+        assert_eq!(
+            selected_texts(&first_two),
+            vec!["Who owns it?", "Can it move?"]
+        );
+    }
+
+    #[test]
+    fn select_shuffles_before_limiting() {
+        let options = QuizOptions {
+            shuffle: true,
+            limit: Some(3),
+            ..Default::default()
+        };
+
+        let selected = selected_texts(&options);
+
+        assert_eq!(selected.len(), 3);
+        assert_ne!(
+            selected,
+            vec!["Who owns it?", "Can it move?", "Is it a trait?"]
+        );
+        assert_eq!(selected, selected_texts(&options));
+    }
+
+    #[test]
+    fn quiz_scores_only_selected_questions() {
+        let mut inter = FakeInterface::with_stdin("b\n");
+        let mut store = MemoryStore::new().with_quiz_file(TAGGED_QUIZ);
+        let options = QuizOptions {
+            tags: vec!["traits".to_string()],
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Is it a trait?\n a) no one\n b) yes\n>>> Correct!\n\n\
+            You scored 1 out of 1 points.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn quiz_says_when_no_questions_match() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(TAGGED_QUIZ);
+        let options = QuizOptions {
+            tags: vec!["lifetimes".to_string()],
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            No questions match the selected tags and difficulty.\n\
+            "
+        );
+    }
+
+    const TIMED_QUIZ: &str = r#"{"version":1,"questions":[
+        {"text":"One?","correct":"1","incorrects":["0"]},
+        {"text":"Two?","correct":"2","incorrects":["0"]},
+        {"text":"Three?","correct":"3","incorrects":["0"]}
+    ]}"#;
+
+    #[test]
+    fn runs_are_added_to_the_history() {
+        let mut inter = FakeInterface::with_stdin("b\nwrong\n").with_delays(&[3, 4]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        let history = crate::stats::load_history(&mut store, DEFAULT_QUIZ_FILE).unwrap();
+        assert_eq!(history.runs.len(), 1);
+        let run = &history.runs[0];
+        assert_eq!((run.score, run.total), (1.0, 3));
+        let answered: Vec<(bool, Option<f64>)> = run
+            .questions
+            .iter()
+            .map(|q| (q.correct, q.seconds))
+            .collect();
+        assert_eq!(
+            answered,
+            [(true, Some(3.0)), (false, Some(4.0)), (false, None)]
+        );
+    }
+
+    #[test]
+    fn slow_answer_times_out_and_counts_as_wrong() {
+        let mut inter = FakeInterface::with_stdin("b\nb\nb\n").with_delays(&[3, 12, 5]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+        let options = QuizOptions {
+            per_question_timeout: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            One?\n a) 0\n b) 1\n>>> Correct!\n\n\
+            Two?\n a) 0\n b) 2\n>>> \nTime's up!\n\n\
+            Three?\n a) 0\n b) 3\n>>> Correct!\n\n\
+            Time per question:\n  \
+              1. One?: 3.0s\n  \
+              2. Two?: 10.0s (timed out)\n  \
+              3. Three?: 5.0s\n\
+            \n\
+            You scored 2 out of 3 points.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn late_answer_does_not_answer_next_question() {
+        let mut inter = FakeInterface::with_stdin("b\nb\na\n").with_delays(&[3, 12, 2]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+        let options = QuizOptions {
+            per_question_timeout: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert!(inter.stdout().ends_with(
+            "\
+            Three?\n a) 0\n b) 3\n>>> Wrong answer.\n\n\
+            Time per question:\n  \
+              1. One?: 3.0s\n  \
+              2. Two?: 10.0s (timed out)\n  \
+              3. Three?: 2.0s\n\
+            \n\
+            You scored 1 out of 3 points.\n\
+            "
+        ));
+    }
+
+    #[test]
+    fn total_time_limit_ends_quiz() {
+        let mut inter = FakeInterface::with_stdin("b\nb\nb\n").with_delays(&[20, 20, 20]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+        let options = QuizOptions {
+            time_limit: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert!(inter.stdout().ends_with(
+            "\
+            Two?\n a) 0\n b) 2\n>>> \nTime's up!\n\n\
+            Out of time! The 1 remaining questions count as wrong.\n\n\
+            Time per question:\n  \
+              1. One?: 20.0s\n  \
+              2. Two?: 10.0s (timed out)\n  \
+              3. Three?: not answered\n\
+            \n\
+            You scored 1 out of 3 points.\n\
+            "
+        ));
+    }
+
+    #[test]
+    fn untimed_quiz_does_not_report_times() {
+        let mut inter = FakeInterface::with_stdin("b\nb\nb\n").with_delays(&[20, 20, 20]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert!(!inter.stdout().contains("Time per question"));
+        assert!(inter.stdout().ends_with("You scored 3 out of 3 points.\n"));
+    }
+
+    #[test]
+    fn quiz_reports_partial_score_when_input_ends() {
+        let mut inter = FakeInterface::with_stdin("b\n");
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert!(inter.stdout().ends_with(
+            "\
+            Two?\n a) 0\n b) 2\n>>> \n\
+            Input ended after 1 of 3 questions. The 2 remaining questions count as wrong.\n\n\
+            You scored 1 out of 3 points.\n\
+            "
+        ));
+    }
+
+    #[test]
+    fn quiz_returns_error_when_reading_fails() {
+        let mut inter =
+            FakeInterface::with_stdin("b\n").with_stdin_error(std::io::ErrorKind::BrokenPipe);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+
+        let err = run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, QuizError::Io(_)));
+        assert!(inter.stdout().ends_with("You scored 1 out of 3 points.\n"));
+    }
+
+    #[test]
+    fn quiz_returns_error_for_invalid_file() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file("not json");
+
+        let err = run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, QuizError::Load { .. }));
+        assert!(err.to_string().starts_with("Failed to load quiz.json: "));
+        assert_eq!(inter.stdout(), "");
+    }
+
+    #[test]
+    fn quiz_writes_results_file() {
+        let mut inter = FakeInterface::with_stdin("b\n1\na\n").with_delays(&[3, 12]);
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+        let options = QuizOptions {
+            per_question_timeout: Some(Duration::from_secs(10)),
+            results: Some("results.json".to_string()),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(store.list_quiz_files(".").unwrap(), [DEFAULT_QUIZ_FILE]);
+        let results: serde_json::Value =
+            serde_json::from_str(store.file("results.json").unwrap()).unwrap();
+        assert_eq!(
+            results,
+            serde_json::json!({
+                "quiz": "quiz.json",
+                "score": 1.0,
+                "total": 3,
+                "questions": [
+                    {
+                        "question": "One?",
+                        "correct_answer": "1",
+                        "answer": "b",
+                        "points": 1.0,
+                        "correct": true,
+                        "timed_out": false,
+                        "seconds": 3.0,
+                    },
+                    {
+                        "question": "Two?",
+                        "correct_answer": "2",
+                        "answer": null,
+                        "points": 0.0,
+                        "correct": false,
+                        "timed_out": true,
+                        "seconds": 10.0,
+                    },
+                    {
+                        "question": "Three?",
+                        "correct_answer": "3",
+                        "answer": "a",
+                        "points": 0.0,
+                        "correct": false,
+                        "timed_out": false,
+                        "seconds": 0.0,
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn quiz_writes_junit_results_for_xml_file() {
+        let mut inter = FakeInterface::with_stdin("b\n");
+        let mut store = MemoryStore::new().with_quiz_file(TIMED_QUIZ);
+        let options = QuizOptions {
+            results: Some("results.xml".to_string()),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        let xml = store.file("results.xml").unwrap();
+        assert!(xml.contains("<testsuite name=\"quiz.json\" tests=\"3\" failures=\"2\""));
+        assert!(
+            xml.contains("<testcase name=\"3. Three?\">\n    <failure message=\"not answered\"/>")
+        );
+    }
+
+    #[test]
+    fn explanations_follow_answers_and_review_lists_missed_questions() {
+        let mut inter = FakeInterface::with_stdin("Lyon\n42\n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"version":2,"questions":[
+                {"text":"Capital of France?","kind":"free_text","accepted":["Paris"],
+                 "explanation":"Lyon is the third largest city.","reference":"Atlas, page 12"},
+                {"text":"6 * 7?","kind":"numeric","answer":42},
+                {"text":"Boiling point of water in Celsius?","kind":"numeric","answer":100}
+            ]}"#,
+        );
+        let options = QuizOptions {
+            review: true,
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Capital of France?\n>>> Wrong answer.\n\
+            Explanation: Lyon is the third largest city.\n\
+            Reference: Atlas, page 12\n\n\
+            6 * 7?\n>>> Correct!\n\n\
+            Boiling point of water in Celsius?\n>>> \n\
+            Input ended after 2 of 3 questions. The 1 remaining questions count as wrong.\n\n\
+            You scored 1 out of 3 points.\n\
+            \n\
+            Review of the missed questions:\n\
+            \n\
+            Capital of France?\n\
+            Your answer: Lyon\n\
+            Correct answer: Paris\n\
+            Explanation: Lyon is the third largest city.\n\
+            Reference: Atlas, page 12\n\
+            \n\
+            Boiling point of water in Celsius?\n\
+            Your answer: none, it was not answered\n\
+            Correct answer: 100\n\
+            "
+        );
+    }
+
+    #[test]
+    fn quiz_scores_each_kind_of_question() {
+        let mut inter = FakeInterface::with_stdin("2, 3\n paris \n0.34\n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"version":2,"questions":[
+                {"text":"Primes?","kind":"multi_select","corrects":["2","3","5"],"incorrects":["4"],"partial":true},
+                {"text":"Capital of France?","kind":"free_text","accepted":["Paris"]},
+                {"text":"1/3?","kind":"numeric","answer":0.33,"tolerance":0.01}
+            ]}"#,
+        );
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        let stdout = inter.stdout();
+        assert!(stdout.contains("(Pick every correct answer, separated by commas)\n>>> "));
+        assert!(stdout.contains("Partly correct, 0.67 of a point.\n"));
+        assert!(stdout.contains("Capital of France?\n>>> Correct!\n"));
+        assert!(stdout.ends_with("You scored 2.67 out of 3 points.\n"));
+    }
+
+    #[test]
+    fn multi_select_accepts_choice_labels() {
+        let mut inter = FakeInterface::with_stdin("a, b\n");
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"version":2,"questions":[
+                {"text":"Languages?","kind":"multi_select","corrects":["Rust","C"],"incorrects":[]}
+            ]}"#,
+        );
+
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert!(
+            inter
+                .stdout()
+                .ends_with("Correct!\n\nYou scored 1 out of 1 points.\n")
+        );
+    }
 }
@@ -1,52 +1,248 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines, Read, Stdin, Stdout, Write, stdin, stdout};
+use std::io::{self, BufRead, Write, stdin, stdout};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-use crate::interface::Interface;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::i18n::{Lang, Messages};
+use crate::interface::{Interface, TimedLine};
 
 pub struct RealInterface {
-    stdin_lines: Lines<BufReader<Stdin>>,
-    stdout: Stdout,
+    /// Lines read from stdin by a background thread, so that reading can time
+    /// out. The thread is started on the first read.
+    stdin_lines: Option<Receiver<io::Result<String>>>,
+    /// Whether the last read timed out. The reader thread is then still
+    /// waiting for that line, which must not be taken as the next answer.
+    timed_out: bool,
+    stdout: Box<dyn Write>,
+    /// The first error reading stdin.
+    read_error: Option<io::Error>,
+    /// The first error writing stdout. Once set, further output is dropped.
+    write_error: Option<io::Error>,
+    rng: StdRng,
+    messages: Messages,
 }
 
 impl RealInterface {
     pub fn new() -> Self {
         Self {
-            // Must bring in BufRead trait in order to call .lines() method.
-            stdin_lines: BufReader::new(stdin()).lines(),
-            stdout: stdout(),
+            stdin_lines: None,
+            timed_out: false,
+            stdout: Box::new(stdout()),
+            read_error: None,
+            write_error: None,
+            rng: StdRng::from_os_rng(),
+            messages: Messages::default(),
         }
     }
+
+    /// Seeds the random number generator, so that every run with the same
+    /// seed shuffles the answer choices the same way.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Shows the messages in `lang`.
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.messages = Messages::new(lang);
+        self
+    }
+
+    fn stdin_lines(&mut self) -> &Receiver<io::Result<String>> {
+        self.stdin_lines.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                // Must bring in BufRead trait in order to call .lines() method.
+                for line in stdin().lock().lines() {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+            rx
+        })
+    }
+
+    /// Drops the lines entered after the last read timed out, so that a late
+    /// answer doesn't answer the next question. A line still being typed when
+    /// the next question is shown can't be told apart from an answer to it.
+    fn drop_late_lines(&mut self) {
+        if !std::mem::take(&mut self.timed_out) {
+            return;
+        }
+        while let Some(Ok(line)) = self.stdin_lines.as_ref().map(Receiver::try_recv) {
+            if let Err(e) = line {
+                self.read_error.get_or_insert(e);
+            }
+        }
+    }
+}
+
+impl Default for RealInterface {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interface for RealInterface {
     fn read_stdin_line(&mut self) -> Option<String> {
-        self.stdin_lines
-            .next() // get the next Lines BufRead line -> Option<Result<String, std::io::Error>>
-            .transpose() // Swap Option and Result -> Result<Option<String>, std::io::Error>
-            .expect("Failed to read stdin") // If something went wrong, then panic.
+        self.drop_late_lines();
+        let res = self
+            .stdin_lines()
+            .recv() // wait for the next line; Err once stdin is closed -> Result<Result<String, _>, _>
+            .ok() // stdin closed -> Option<Result<String, std::io::Error>>
+            .transpose(); // Swap Option and Result -> Result<Option<String>, std::io::Error>
+        match res {
+            Ok(line) => line,
+            Err(e) => {
+                self.read_error.get_or_insert(e);
+                None
+            }
+        }
+    }
+
+    fn read_stdin_line_timeout(&mut self, timeout: Duration) -> TimedLine {
+        self.drop_late_lines();
+        match self.stdin_lines().recv_timeout(timeout) {
+            Ok(Ok(line)) => TimedLine::Line(line),
+            Ok(Err(e)) => {
+                self.read_error.get_or_insert(e);
+                TimedLine::Closed
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out = true;
+                TimedLine::TimedOut
+            }
+            Err(RecvTimeoutError::Disconnected) => TimedLine::Closed,
+        }
     }
 
     fn write_stdout(&mut self, text: &str) {
-        write!(self.stdout, "{}", text).expect("Failed to write stdout");
-        self.stdout.flush().unwrap();
+        if self.write_error.is_some() {
+            return;
+        }
+        let res = write!(self.stdout, "{}", text).and_then(|()| self.stdout.flush());
+        if let Err(e) = res {
+            self.write_error = Some(e);
+        }
     }
 
     fn write_stdout_line(&mut self, text: &str) {
-        writeln!(self.stdout, "{}", text).expect("Failed to write stdout");
+        if self.write_error.is_some() {
+            return;
+        }
+        if let Err(e) = writeln!(self.stdout, "{}", text) {
+            self.write_error = Some(e);
+        }
+    }
+
+    fn take_io_error(&mut self) -> Option<io::Error> {
+        self.read_error.take().or_else(|| self.write_error.take())
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        &mut self.rng
     }
 
-    fn read_quiz_json(&mut self) -> anyhow::Result<String> {
-        let mut file = File::open("quiz.json")?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
-        Ok(contents)
+    fn messages(&self) -> &Messages {
+        &self.messages
     }
 
-    /// Writes quiz contents to the quiz file.
-    /// Relies on [add] to update the existing quiz.
-    fn write_quiz_json(&mut self, text: &str) -> anyhow::Result<()> {
-        let mut file = File::create("quiz.json")?;
-        file.write_all(text.as_bytes())?;
-        Ok(())
+    fn now(&mut self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    /// Stdout that keeps what is written, failing once `fail` is set.
+    #[derive(Clone, Default)]
+    struct Output {
+        text: Rc<RefCell<Vec<u8>>>,
+        fail: Rc<RefCell<bool>>,
+    }
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if *self.fail.borrow() {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.text.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn with_output(stdin_lines: Receiver<io::Result<String>>, output: &Output) -> RealInterface {
+        RealInterface {
+            stdin_lines: Some(stdin_lines),
+            stdout: Box::new(output.clone()),
+            ..RealInterface::new()
+        }
+    }
+
+    #[test]
+    fn output_is_written_after_reading_fails() {
+        let (tx, rx) = mpsc::channel();
+        let output = Output::default();
+        let mut inter = with_output(rx, &output);
+
+        tx.send(Err(io::ErrorKind::InvalidData.into())).unwrap();
+        assert_eq!(inter.read_stdin_line(), None);
+        inter.write_stdout_line("You scored 0 out of 1 points.");
+
+        assert_eq!(
+            String::from_utf8(output.text.take()).unwrap(),
+            "You scored 0 out of 1 points.\n"
+        );
+        let err = inter.take_io_error().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn output_is_dropped_after_writing_fails() {
+        let (_tx, rx) = mpsc::channel();
+        let output = Output::default();
+        let mut inter = with_output(rx, &output);
+
+        inter.write_stdout("One");
+        *output.fail.borrow_mut() = true;
+        inter.write_stdout_line("Two");
+        *output.fail.borrow_mut() = false;
+        inter.write_stdout_line("Three");
+
+        assert_eq!(String::from_utf8(output.text.take()).unwrap(), "One");
+        let err = inter.take_io_error().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn line_entered_after_timeout_is_dropped() {
+        let (tx, rx) = mpsc::channel();
+        let mut inter = RealInterface {
+            stdin_lines: Some(rx),
+            ..RealInterface::new()
+        };
+
+        let res = inter.read_stdin_line_timeout(Duration::from_millis(10));
+        assert!(matches!(res, TimedLine::TimedOut));
+
+        tx.send(Ok("late".to_string())).unwrap();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            tx.send(Ok("next".to_string())).unwrap();
+        });
+        assert_eq!(inter.read_stdin_line().as_deref(), Some("next"));
+        sender.join().unwrap();
     }
 }
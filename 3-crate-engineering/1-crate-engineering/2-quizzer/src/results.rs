@@ -0,0 +1,252 @@
+//! Machine-readable records of a quiz run, for grading students automatically.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::quiz::fmt_points;
+
+/// The formats the results of a quiz run can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ResultsFormat {
+    /// A JSON object with the score and a record of every question
+    Json,
+    /// A JUnit XML test suite with a test case per question
+    Junit,
+}
+
+impl ResultsFormat {
+    /// Guesses the format from the extension of `path`: JUnit for `.xml`
+    /// files, JSON otherwise.
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("xml") => ResultsFormat::Junit,
+            _ => ResultsFormat::Json,
+        }
+    }
+}
+
+/// The outcome of a whole quiz run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizResults {
+    /// The quiz file the questions came from.
+    pub quiz: String,
+    /// The answers were recorded but not scored, as the quiz is sealed with a
+    /// key that was not given. They can be scored with `grade --key`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unscored: bool,
+    /// The points scored, which can be fractional with partial credit.
+    pub score: f64,
+    pub total: usize,
+    pub questions: Vec<QuestionResult>,
+}
+
+/// How one of the questions was answered.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuestionResult {
+    pub question: String,
+    pub correct_answer: String,
+    /// The answer given, or `None` if the question timed out or was not asked.
+    pub answer: Option<String>,
+    /// The points scored for this question, from 0 to 1.
+    pub points: f64,
+    pub correct: bool,
+    pub timed_out: bool,
+    /// How long answering took, or `None` if the question was not asked.
+    pub seconds: Option<f64>,
+}
+
+/// Formats the results of a quiz run.
+pub fn format_results(format: ResultsFormat, results: &QuizResults) -> anyhow::Result<String> {
+    match format {
+        ResultsFormat::Json => Ok(serde_json::to_string_pretty(results)?),
+        ResultsFormat::Junit => Ok(format_junit(results)),
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn format_junit(results: &QuizResults) -> String {
+    let total_secs: f64 = results.questions.iter().filter_map(|q| q.seconds).sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    // Writing to a String cannot fail.
+    let _ = writeln!(
+        xml,
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.1}\">",
+        escape_xml(&results.quiz),
+        results.total,
+        if results.unscored {
+            0
+        } else {
+            results.questions.iter().filter(|q| !q.correct).count()
+        },
+        total_secs
+    );
+
+    for (idx, q) in results.questions.iter().enumerate() {
+        let name = escape_xml(&format!("{}. {}", idx + 1, q.question));
+        let time = q
+            .seconds
+            .map(|secs| format!(" time=\"{:.1}\"", secs))
+            .unwrap_or_default();
+
+        if results.unscored {
+            let _ = writeln!(xml, "  <testcase name=\"{}\"{}>", name, time);
+            xml.push_str("    <skipped message=\"not scored, the quiz is sealed with a key\"/>\n");
+            xml.push_str("  </testcase>\n");
+            continue;
+        }
+
+        let failure = if q.correct {
+            None
+        } else if q.timed_out {
+            Some("timed out".to_string())
+        } else {
+            match &q.answer {
+                Some(answer) if q.points > 0.0 => Some(format!(
+                    "expected '{}', got '{}' for {} points",
+                    q.correct_answer,
+                    answer,
+                    fmt_points(q.points)
+                )),
+                Some(answer) => Some(format!("expected '{}', got '{}'", q.correct_answer, answer)),
+                None => Some("not answered".to_string()),
+            }
+        };
+
+        match failure {
+            None => {
+                let _ = writeln!(xml, "  <testcase name=\"{}\"{}/>", name, time);
+            }
+            Some(message) => {
+                let _ = writeln!(xml, "  <testcase name=\"{}\"{}>", name, time);
+                let _ = writeln!(xml, "    <failure message=\"{}\"/>", escape_xml(&message));
+                xml.push_str("  </testcase>\n");
+            }
+        }
+    }
+
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+/// Escapes text for use in an XML attribute.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> QuizResults {
+        QuizResults {
+            quiz: "quiz.json".to_string(),
+            unscored: false,
+            score: 1.0,
+            total: 3,
+            questions: vec![
+                QuestionResult {
+                    question: "Is 1 < 2?".to_string(),
+                    correct_answer: "yes".to_string(),
+                    answer: Some("a".to_string()),
+                    points: 1.0,
+                    correct: true,
+                    timed_out: false,
+                    seconds: Some(2.0),
+                },
+                QuestionResult {
+                    question: "Rust & C?".to_string(),
+                    correct_answer: "both".to_string(),
+                    answer: Some("neither".to_string()),
+                    points: 0.0,
+                    correct: false,
+                    timed_out: false,
+                    seconds: Some(1.5),
+                },
+                QuestionResult {
+                    question: "Last?".to_string(),
+                    correct_answer: "yes".to_string(),
+                    answer: None,
+                    points: 0.0,
+                    correct: false,
+                    timed_out: false,
+                    seconds: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn format_is_guessed_from_extension() {
+        assert_eq!(ResultsFormat::from_path("out.XML"), ResultsFormat::Junit);
+        assert_eq!(ResultsFormat::from_path("out.json"), ResultsFormat::Json);
+        assert_eq!(ResultsFormat::from_path("results"), ResultsFormat::Json);
+    }
+
+    #[test]
+    fn junit_has_a_failure_per_wrong_answer() {
+        let xml = format_results(ResultsFormat::Junit, &results()).unwrap();
+
+        assert_eq!(
+            xml,
+            "\
+            <?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <testsuite name=\"quiz.json\" tests=\"3\" failures=\"2\" time=\"3.5\">\n  \
+              <testcase name=\"1. Is 1 &lt; 2?\" time=\"2.0\"/>\n  \
+              <testcase name=\"2. Rust &amp; C?\" time=\"1.5\">\n    \
+                <failure message=\"expected &apos;both&apos;, got &apos;neither&apos;\"/>\n  \
+              </testcase>\n  \
+              <testcase name=\"3. Last?\">\n    \
+                <failure message=\"not answered\"/>\n  \
+              </testcase>\n\
+            </testsuite>\n\
+            "
+        );
+    }
+
+    #[test]
+    fn json_records_every_question() {
+        let json = format_results(ResultsFormat::Json, &results()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["score"], 1.0);
+        assert_eq!(value["questions"][1]["answer"], "neither");
+        assert_eq!(value["questions"][2]["answer"], serde_json::Value::Null);
+        assert_eq!(value["questions"][2]["seconds"], serde_json::Value::Null);
+        assert_eq!(value.get("unscored"), None);
+    }
+
+    #[test]
+    fn unscored_results_skip_every_question() {
+        let results = QuizResults {
+            unscored: true,
+            ..results()
+        };
+
+        let xml = format_results(ResultsFormat::Junit, &results).unwrap();
+        assert!(xml.contains("tests=\"3\" failures=\"0\""), "{}", xml);
+        assert_eq!(xml.matches("<skipped ").count(), 3);
+        assert!(!xml.contains("<failure "));
+
+        let json = format_results(ResultsFormat::Json, &results).unwrap();
+        let parsed: QuizResults = serde_json::from_str(&json).unwrap();
+        assert!(parsed.unscored);
+        assert_eq!(parsed.questions[1].answer.as_deref(), Some("neither"));
+    }
+}
@@ -0,0 +1,196 @@
+//! Versioning of the on-disk quiz format.
+//!
+//! Every quiz file records the `version` of the format it was written in.
+//! Older files are upgraded one version at a time by [MIGRATIONS] before being
+//! deserialized, so that adding fields to [Quiz] does not break existing quizzes.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::quiz::Quiz;
+
+/// The version of the quiz format written by this build of quizzer.
+pub const CURRENT_VERSION: u64 = 3;
+
+/// Upgrades a quiz document from version `i` to version `i + 1`.
+type Migration = fn(&mut Map<String, Value>);
+
+/// The migrations, indexed by the version they upgrade from.
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// Why a quiz file could not be read.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The file was written by a newer quizzer than this one.
+    NewerVersion {
+        found: u64,
+    },
+    /// The `version` field is not a whole number.
+    InvalidVersion(Value),
+    /// The top level of the file is not a JSON object.
+    NotAnObject,
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::NewerVersion { found } => write!(
+                f,
+                "the quiz uses format version {}, but this quizzer only supports up to version {}; \
+                 please upgrade quizzer",
+                found, CURRENT_VERSION
+            ),
+            SchemaError::InvalidVersion(v) => write!(f, "invalid format version {}", v),
+            SchemaError::NotAnObject => write!(f, "the quiz is not a JSON object"),
+            SchemaError::Json(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SchemaError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SchemaError {
+    fn from(e: serde_json::Error) -> Self {
+        SchemaError::Json(e)
+    }
+}
+
+/// Parses a quiz file of any supported version, upgrading it to the current format.
+pub fn parse_quiz(contents: &str) -> Result<Quiz, SchemaError> {
+    parse_quiz_value(serde_json::from_str(contents)?)
+}
+
+/// Reads a quiz of any supported version that has already been parsed, such
+/// as one imported from YAML, upgrading it to the current format.
+pub fn parse_quiz_value(doc: Value) -> Result<Quiz, SchemaError> {
+    let Value::Object(mut doc) = doc else {
+        return Err(SchemaError::NotAnObject);
+    };
+
+    // Files written before versioning was introduced have no version field.
+    let version = match doc.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .ok_or_else(|| SchemaError::InvalidVersion(v.clone()))?,
+    };
+
+    if version > CURRENT_VERSION {
+        return Err(SchemaError::NewerVersion { found: version });
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut doc);
+    }
+
+    doc.remove("version");
+    Ok(serde_json::from_value(Value::Object(doc))?)
+}
+
+/// Serializes a quiz in the current format.
+pub fn serialize_quiz(quiz: &Quiz) -> Result<String, SchemaError> {
+    let Value::Object(mut doc) = serde_json::to_value(quiz)? else {
+        return Err(SchemaError::NotAnObject);
+    };
+    doc.insert("version".to_string(), CURRENT_VERSION.into());
+    Ok(serde_json::to_string(&doc)?)
+}
+
+/// Version 0 quizzes are the unversioned `{"questions": [...]}` files.
+fn migrate_v0_to_v1(doc: &mut Map<String, Value>) {
+    doc.entry("questions")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    doc.insert("version".to_string(), 1.into());
+}
+
+/// Version 2 added question kinds; every earlier question is multiple choice.
+fn migrate_v1_to_v2(doc: &mut Map<String, Value>) {
+    if let Some(Value::Array(questions)) = doc.get_mut("questions") {
+        for question in questions {
+            if let Value::Object(question) = question {
+                question
+                    .entry("kind")
+                    .or_insert_with(|| "multiple_choice".into());
+            }
+        }
+    }
+    doc.insert("version".to_string(), 2.into());
+}
+
+/// Version 3 added sealed questions. Earlier quizzes have none, so only the
+/// version changes, but quizzers that cannot run sealed questions refuse the file.
+fn migrate_v2_to_v3(doc: &mut Map<String, Value>) {
+    doc.insert("version".to_string(), 3.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::kind::{Kind, QuestionKind};
+
+    use super::*;
+
+    #[test]
+    fn unversioned_quiz_is_migrated() {
+        let quiz =
+            parse_quiz(r#"{"questions":[{"text":"True?","correct":"yes","incorrects":["no"]}]}"#)
+                .unwrap();
+
+        assert_eq!(quiz.len(), 1);
+        assert_eq!(
+            quiz.questions[0].kind,
+            QuestionKind::MultipleChoice {
+                correct: "yes".to_string(),
+                incorrects: vec!["no".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn v1_questions_become_multiple_choice() {
+        let quiz = parse_quiz(
+            r#"{"version":1,"questions":[{"text":"True?","correct":"yes","incorrects":["no"]}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(quiz.questions[0].kind.kind(), Kind::MultipleChoice);
+    }
+
+    #[test]
+    fn saved_quiz_has_current_version() {
+        let quiz = parse_quiz(r#"{"questions":[]}"#).unwrap();
+
+        let json = serialize_quiz(&quiz).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            json!({ "version": CURRENT_VERSION, "questions": [] })
+        );
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let err = parse_quiz(r#"{"version":99,"questions":[]}"#).unwrap_err();
+
+        assert!(matches!(err, SchemaError::NewerVersion { found: 99 }));
+        assert!(err.to_string().contains("please upgrade quizzer"));
+    }
+
+    #[test]
+    fn invalid_version_is_rejected() {
+        let err = parse_quiz(r#"{"version":"one","questions":[]}"#).unwrap_err();
+
+        assert!(matches!(err, SchemaError::InvalidVersion(_)));
+    }
+}
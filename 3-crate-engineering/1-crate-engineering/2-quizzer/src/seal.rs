@@ -0,0 +1,541 @@
+//! Sealed quizzes, which can be handed out without giving away the answers.
+//!
+//! Sealing replaces the correct answers of every question with salted SHA-256
+//! hashes of them, which is enough to check an answer but not to read one.
+//! Anyone with the file can still hash a guess, though, so a question whose
+//! answer is one of a few choices or a number could be worked out. Those can
+//! only be sealed with a key, which the hashes are then keyed with (HMAC): the
+//! answers can only be checked by whoever has the key, such as a teacher
+//! running `quiz`, `serve` or `grade` with it, and not by the students.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{QuizError, check_io},
+    interface::Interface,
+    kind::{Kind, QuestionKind},
+    quiz::{Question, Quiz, load_quiz, normalize_answer},
+    schema::serialize_quiz,
+    store::QuizStore,
+};
+
+/// What is hashed with the key of a keyed question to tell whether a key
+/// given later is the right one. Answers are normalized and never have a NUL.
+const KEY_CHECK: &str = "\0key";
+
+/// Writes the quiz in `quiz_file` to stdout sealed, to be saved and run like
+/// any other quiz. With a `key`, running or grading it takes the key too.
+pub fn export_sealed(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    key: Option<&str>,
+) -> Result<(), QuizError> {
+    let quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+
+    let json = seal_quiz(&quiz, inter.rng(), key)
+        .and_then(|quiz| Ok(serialize_quiz(&quiz)?))
+        .map_err(|source| QuizError::Seal {
+            file: quiz_file.to_string(),
+            source,
+        })?;
+    inter.write_stdout_line(&json);
+    check_io(inter)
+}
+
+/// Seals every question of `quiz`, with a new random salt for each, keying
+/// the hashes with `key` if given. The explanations are left out, as they
+/// would often give the answer away.
+pub fn seal_quiz(quiz: &Quiz, rng: &mut dyn RngCore, key: Option<&str>) -> anyhow::Result<Quiz> {
+    let mut questions = Vec::with_capacity(quiz.questions.len());
+    for (idx, q) in quiz.questions.iter().enumerate() {
+        let kind = seal_kind(&q.kind, rng, key).map_err(|reason| {
+            anyhow::anyhow!("question {} cannot be sealed: {}", idx + 1, reason)
+        })?;
+        questions.push(Question {
+            kind,
+            explanation: None,
+            ..q.clone()
+        });
+    }
+    Ok(Quiz { questions })
+}
+
+/// Why the answers of a quiz sealed with a key cannot be checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locked {
+    /// No key was given.
+    NoKey,
+    /// The key given is not the one the quiz was sealed with.
+    WrongKey,
+}
+
+/// Loads the quiz in `quiz_file` and unlocks it with `key`, for the commands
+/// that need to check answers.
+pub(crate) fn load_unlocked(
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    key: Option<&str>,
+) -> Result<Quiz, QuizError> {
+    let mut quiz = load_quiz(store, quiz_file).map_err(|source| QuizError::Load {
+        file: quiz_file.to_string(),
+        source,
+    })?;
+    unlock_quiz(&mut quiz, key).map_err(|reason| QuizError::Sealed {
+        file: quiz_file.to_string(),
+        reason,
+    })?;
+    Ok(quiz)
+}
+
+/// Gives `key` to the keyed questions of `quiz`, so that their answers can be
+/// checked. Fails if the quiz has keyed questions and `key` is not theirs.
+pub fn unlock_quiz(quiz: &mut Quiz, key: Option<&str>) -> Result<(), Locked> {
+    for q in &mut quiz.questions {
+        if let QuestionKind::Sealed {
+            salt,
+            key_check: Some(check),
+            key: question_key,
+            ..
+        } = &mut q.kind
+        {
+            let key = key.ok_or(Locked::NoKey)?;
+            if salted_hash(Some(key), salt, KEY_CHECK) != *check {
+                return Err(Locked::WrongKey);
+            }
+            *question_key = Some(key.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn seal_kind(
+    kind: &QuestionKind,
+    rng: &mut dyn RngCore,
+    key: Option<&str>,
+) -> Result<QuestionKind, &'static str> {
+    let salt = new_salt(rng);
+    let of = kind.kind();
+    if key.is_none() {
+        match of {
+            Kind::MultipleChoice | Kind::MultiSelect if !kind.is_sealed() => {
+                return Err(
+                    "the answer can be worked out by hashing each choice, seal it with --key",
+                );
+            }
+            Kind::Numeric if !kind.is_sealed() => {
+                return Err("the answer can be worked out by hashing numbers, seal it with --key");
+            }
+            _ => {}
+        }
+    }
+    let (choices, hashes, partial) = match kind {
+        QuestionKind::MultipleChoice {
+            correct,
+            incorrects,
+        } => (
+            sorted_choices(std::slice::from_ref(correct), incorrects),
+            vec![hash_answer(key, &salt, of, correct)],
+            false,
+        ),
+        QuestionKind::MultiSelect {
+            corrects,
+            incorrects,
+            partial,
+        } => {
+            let hashes = if *partial {
+                let mut hashes: Vec<String> = corrects
+                    .iter()
+                    .map(|c| hash_answer(key, &salt, of, c))
+                    .collect();
+                hashes.sort();
+                hashes
+            } else {
+                let corrects: Vec<&str> = corrects.iter().map(String::as_str).collect();
+                vec![hash_answers(key, &salt, &corrects)]
+            };
+            (sorted_choices(corrects, incorrects), hashes, *partial)
+        }
+        QuestionKind::FreeText {
+            pattern: Some(_), ..
+        } => {
+            return Err("a pattern cannot be checked against a hash");
+        }
+        QuestionKind::FreeText { accepted, .. } => {
+            let mut hashes: Vec<String> = accepted
+                .iter()
+                .map(|a| hash_answer(key, &salt, of, a))
+                .collect();
+            hashes.sort();
+            hashes.dedup();
+            (Vec::new(), hashes, false)
+        }
+        QuestionKind::Numeric { tolerance, .. } if *tolerance != 0.0 => {
+            return Err("a tolerance cannot be checked against a hash");
+        }
+        QuestionKind::Numeric { answer, .. } => (
+            Vec::new(),
+            vec![hash_answer(key, &salt, of, &answer.to_string())],
+            false,
+        ),
+        QuestionKind::Sealed { .. } => return Ok(kind.clone()),
+    };
+    Ok(QuestionKind::Sealed {
+        of,
+        choices,
+        key_check: key.map(|key| salted_hash(Some(key), &salt, KEY_CHECK)),
+        salt,
+        hashes,
+        partial,
+        key: None,
+    })
+}
+
+/// Sorts the choices, so that their order does not tell the correct ones apart.
+fn sorted_choices(corrects: &[String], incorrects: &[String]) -> Vec<String> {
+    let mut choices: Vec<String> = corrects.iter().chain(incorrects).cloned().collect();
+    choices.sort();
+    choices
+}
+
+fn new_salt(rng: &mut dyn RngCore) -> String {
+    let mut salt = [0u8; 16];
+    rng.fill_bytes(&mut salt);
+    to_hex(&salt)
+}
+
+/// Hashes an answer to a question of kind `of`, normalized the way answers
+/// are compared: numbers by value, anything else ignoring case and spacing.
+pub(crate) fn hash_answer(key: Option<&str>, salt: &str, of: Kind, ans: &str) -> String {
+    let normalized = match (of, ans.trim().parse::<f64>()) {
+        (Kind::Numeric, Ok(n)) => n.to_string(),
+        _ => normalize_answer(ans),
+    };
+    salted_hash(key, salt, &normalized)
+}
+
+/// Hashes a set of answers together, in any order.
+pub(crate) fn hash_answers(key: Option<&str>, salt: &str, answers: &[&str]) -> String {
+    let mut normalized: Vec<String> = answers.iter().map(|a| normalize_answer(a)).collect();
+    normalized.sort();
+    normalized.dedup();
+    // Normalized answers have no newlines, so joining them is unambiguous.
+    salted_hash(key, salt, &normalized.join("\n"))
+}
+
+fn salted_hash(key: Option<&str>, salt: &str, text: &str) -> String {
+    let Some(key) = key else {
+        let digest = Sha256::new()
+            .chain_update(salt)
+            .chain_update([0])
+            .chain_update(text)
+            .finalize();
+        return to_hex(&digest);
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(salt.as_bytes());
+    mac.update(&[0]);
+    mac.update(text.as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::grade::grade;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, QuizOptions, run_quiz};
+    use crate::results::QuizResults;
+    use crate::schema::parse_quiz;
+    use crate::stats::history_file;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":3,"questions":[
+        {"text":"Capital of France?","kind":"free_text","accepted":["Paris"],
+         "explanation":"Paris, since 987.","reference":"Chapter 1"},
+        {"text":"2 + 2?","kind":"numeric","answer":4},
+        {"text":"Which are primes?","kind":"multi_select","corrects":["2","3"],"incorrects":["4"]},
+        {"text":"Which is even?","kind":"multiple_choice","correct":"8","incorrects":["7","9"]}
+    ]}"#;
+
+    const KEY: &str = "s3cret";
+
+    fn sealed_json() -> String {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        export_sealed(&mut inter, &mut store, DEFAULT_QUIZ_FILE, Some(KEY)).unwrap();
+
+        inter.stdout().to_string()
+    }
+
+    fn with_key() -> QuizOptions {
+        QuizOptions {
+            key: Some(KEY.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sealed_quiz_keeps_choices_but_not_answers() {
+        let json = sealed_json();
+        let quiz = parse_quiz(&json).unwrap();
+
+        for hidden in ["Paris", "987", "corrects", "\"answer\""] {
+            assert!(!json.contains(hidden), "{} is in {}", hidden, json);
+        }
+        assert!(quiz.questions.iter().all(|q| q.kind.is_sealed()));
+        assert_eq!(quiz.questions[0].reference.as_deref(), Some("Chapter 1"));
+        let QuestionKind::Sealed { of, choices, .. } = &quiz.questions[3].kind else {
+            panic!("not sealed");
+        };
+        assert_eq!(*of, Kind::MultipleChoice);
+        assert_eq!(choices, &["7", "8", "9"]);
+    }
+
+    #[test]
+    fn sealed_quiz_is_graded_by_hashes() {
+        let mut inter = FakeInterface::with_stdin(" PARIS \n4.0\n3, 2\n7\n");
+        let mut store = MemoryStore::new().with_quiz_file(&sealed_json());
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &with_key()).unwrap();
+
+        assert!(inter.stdout().contains("Reference: Chapter 1"));
+        assert!(inter.stdout().ends_with("You scored 3 out of 4 points.\n"));
+    }
+
+    #[test]
+    fn partial_credit_survives_sealing() {
+        let kind = QuestionKind::MultiSelect {
+            corrects: vec!["2".to_string(), "3".to_string()],
+            incorrects: vec!["4".to_string()],
+            partial: true,
+        };
+
+        let mut quiz = Quiz {
+            questions: vec![Question {
+                kind: seal_kind(&kind, &mut StdRng::seed_from_u64(0), Some(KEY)).unwrap(),
+                ..Default::default()
+            }],
+        };
+        unlock_quiz(&mut quiz, Some(KEY)).unwrap();
+        let sealed = &quiz.questions[0].kind;
+
+        assert_eq!(sealed.score_text("2, 3"), 1.0);
+        assert_eq!(sealed.score_text("2"), 0.5);
+        assert_eq!(sealed.score_text("2, 4"), 0.0);
+    }
+
+    #[test]
+    fn answers_that_cannot_be_hashed_are_refused() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(
+            r#"{"version":3,"questions":[{"text":"1/3?","kind":"numeric","answer":0.33,"tolerance":0.01}]}"#,
+        );
+
+        let err = export_sealed(&mut inter, &mut store, DEFAULT_QUIZ_FILE, Some(KEY)).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to seal quiz.json: question 1 cannot be sealed: a tolerance cannot be checked against a hash"
+        );
+        assert_eq!(inter.stdout(), "");
+    }
+
+    #[test]
+    fn choices_and_numbers_are_only_sealed_with_a_key() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        let err = export_sealed(&mut inter, &mut store, DEFAULT_QUIZ_FILE, None).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to seal quiz.json: question 2 cannot be sealed: \
+             the answer can be worked out by hashing numbers, seal it with --key"
+        );
+        assert_eq!(inter.stdout(), "");
+
+        let free_text = r#"{"version":3,"questions":[
+            {"text":"Capital of France?","kind":"free_text","accepted":["Paris"]}
+        ]}"#;
+        let mut store = MemoryStore::new().with_quiz_file(free_text);
+        export_sealed(&mut inter, &mut store, DEFAULT_QUIZ_FILE, None).unwrap();
+        assert!(
+            parse_quiz(inter.stdout()).unwrap().questions[0]
+                .kind
+                .is_sealed()
+        );
+    }
+
+    #[test]
+    fn keyed_choices_cannot_be_worked_out_from_the_file() {
+        let quiz = parse_quiz(&sealed_json()).unwrap();
+        let QuestionKind::Sealed {
+            of,
+            choices,
+            salt,
+            hashes,
+            ..
+        } = &quiz.questions[3].kind
+        else {
+            panic!("not sealed");
+        };
+
+        for choice in choices {
+            assert!(!hashes.contains(&hash_answer(None, salt, *of, choice)));
+        }
+        assert!(hashes.contains(&hash_answer(Some(KEY), salt, *of, "8")));
+    }
+
+    #[test]
+    fn keyed_quiz_is_only_graded_with_its_key() {
+        let mut store = MemoryStore::new()
+            .with_quiz_file(&sealed_json())
+            .with_file("alice.txt", "Paris\n4\n2, 3\n8\n");
+
+        for (key, locked, error) in [
+            (
+                None,
+                Locked::NoKey,
+                "quiz.json is sealed with a key. Pass it with --key, \
+                 or record the answers with --results to grade them later.",
+            ),
+            (
+                Some("guess"),
+                Locked::WrongKey,
+                "quiz.json is sealed with a different key.",
+            ),
+        ] {
+            let mut inter = FakeInterface::with_stdin("Paris\n");
+            let options = QuizOptions {
+                key: key.map(String::from),
+                ..Default::default()
+            };
+            let err = run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap_err();
+            assert!(
+                matches!(&err, QuizError::Sealed { reason, .. } if *reason == locked),
+                "{:?}",
+                err
+            );
+            assert_eq!(err.to_string(), error);
+            assert_eq!(inter.stdout(), "");
+        }
+
+        let mut inter = FakeInterface::new();
+        let sheets = ["alice.txt".to_string()];
+        grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &sheets,
+            Some(KEY),
+        )
+        .unwrap();
+        assert!(
+            inter.stdout().contains("alice    4/4"),
+            "{}",
+            inter.stdout()
+        );
+
+        let mut inter = FakeInterface::new();
+        let err = grade(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &sheets, None).unwrap_err();
+        assert!(matches!(
+            err,
+            QuizError::Sealed {
+                reason: Locked::NoKey,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn keyed_quiz_run_without_its_key_is_recorded_for_grading() {
+        let mut inter = FakeInterface::with_stdin(" PARIS \n4.0\n3, 2\n7\n");
+        let mut store = MemoryStore::new().with_quiz_file(&sealed_json());
+        let options = QuizOptions {
+            results: Some("bob.json".to_string()),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        let out = inter.stdout();
+        assert_eq!(
+            out.matches("not scored but recorded in bob.json").count(),
+            1
+        );
+        assert!(!out.contains("Correct!") && !out.contains("Wrong answer."));
+        assert!(!out.contains("You scored"), "{}", out);
+        assert!(
+            out.ends_with("graded with ./quizzer grade --key\n"),
+            "{}",
+            out
+        );
+        assert!(store.read_file(&history_file(DEFAULT_QUIZ_FILE)).is_err());
+
+        let results: QuizResults =
+            serde_json::from_str(&store.read_file("bob.json").unwrap()).unwrap();
+        assert!(results.unscored);
+        let answers: Vec<_> = results
+            .questions
+            .iter()
+            .map(|q| q.answer.as_deref().unwrap())
+            .collect();
+        assert_eq!(answers, ["PARIS", "4.0", "3, 2", "7"]);
+
+        let mut inter = FakeInterface::new();
+        let sheets = ["bob.json".to_string()];
+        grade(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &sheets,
+            Some(KEY),
+        )
+        .unwrap();
+        assert!(
+            inter.stdout().contains("bob      3/4    + + + -"),
+            "{}",
+            inter.stdout()
+        );
+    }
+
+    #[test]
+    fn recorded_choices_are_kept_by_their_text() {
+        let mut inter = FakeInterface::with_stdin("Paris\n4\nb, a\nc\n");
+        let mut store = MemoryStore::new().with_quiz_file(&sealed_json());
+        let options = QuizOptions {
+            results: Some("bob.json".to_string()),
+            ..Default::default()
+        };
+
+        run_quiz(&mut inter, &mut store, DEFAULT_QUIZ_FILE, &options).unwrap();
+
+        let results: QuizResults =
+            serde_json::from_str(&store.read_file("bob.json").unwrap()).unwrap();
+        let multi = results.questions[2].answer.as_deref().unwrap();
+        assert_eq!(multi.split(", ").count(), 2);
+        assert!(
+            multi
+                .split(", ")
+                .all(|pick| ["2", "3", "4"].contains(&pick))
+        );
+        let single = results.questions[3].answer.as_deref().unwrap();
+        assert!(["7", "8", "9"].contains(&single), "{}", single);
+    }
+}
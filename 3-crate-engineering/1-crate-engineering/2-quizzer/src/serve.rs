@@ -0,0 +1,754 @@
+//! A small HTTP/JSON API for running a quiz for a room of students, each
+//! answering in their own session:
+//!
+//! - `POST /sessions` with `{"name": "..."}` starts a session.
+//! - `GET /sessions/<id>/question` returns the question to answer next.
+//! - `POST /sessions/<id>/answer` with `{"answer": "..."}` answers it.
+//! - `GET /sessions/<id>/score` returns the score so far.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::{Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use rand::RngCore;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tiny_http::{Header, Request, Response, Server};
+
+use crate::{
+    check::warn_problems,
+    error::{QuizError, check_io},
+    interface::Interface,
+    kind::Kind,
+    quiz::{Question, QuizOptions, choice_label, fmt_points, score_answer, shown_choices},
+    seal::load_unlocked,
+    store::QuizStore,
+};
+
+/// The largest request body read, which is plenty for an answer.
+const MAX_BODY: u64 = 64 * 1024;
+
+/// How many request bodies are read at once.
+const READERS: usize = 8;
+
+/// How long a finished session is kept after its last request, for its score
+/// to be looked up.
+const FINISHED_SESSION: Duration = Duration::from_secs(10 * 60);
+
+/// How long a session that is not finished is kept after its last request.
+const IDLE_SESSION: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Serves the quiz in `quiz_file` on `port` of `host`. Only this computer can
+/// reach 127.0.0.1, while students on the same network can reach 0.0.0.0.
+/// Each session asks the questions chosen by `options`. Runs until the
+/// process is stopped.
+pub fn serve(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    host: &str,
+    port: u16,
+    options: QuizOptions,
+) -> Result<(), QuizError> {
+    let quiz = load_unlocked(store, quiz_file, options.key.as_deref())?;
+
+    if quiz.is_empty() {
+        let msg = inter.messages().get("no-questions");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+    warn_problems(inter, quiz_file, &quiz);
+
+    let address = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let server = Server::http((host, port)).map_err(|e| QuizError::Serve {
+        address: address.clone(),
+        source: anyhow::anyhow!(e),
+    })?;
+    let msg = inter.messages().format(
+        "serve-started",
+        &[("file", quiz_file.into()), ("address", address.into())],
+    );
+    inter.write_stdout_line(&msg);
+
+    let mut classroom = Classroom::new(quiz.questions, options);
+    respond(inter, &mut classroom, server.incoming_requests());
+    check_io(inter)
+}
+
+/// Answers each of `requests` once its body has been read. Bodies are read
+/// by a few threads, so that a student on a slow connection only holds up
+/// their own request, while the sessions are kept on this thread.
+fn respond(
+    inter: &mut dyn Interface,
+    classroom: &mut Classroom,
+    requests: impl Iterator<Item = Request> + Send,
+) {
+    let requests = Mutex::new(requests);
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..READERS {
+            let tx = tx.clone();
+            let requests = &requests;
+            scope.spawn(move || {
+                // The lock is only held while waiting for the next request.
+                while let Some(mut request) = requests.lock().ok().and_then(|mut r| r.next()) {
+                    let body = read_body(&mut request);
+                    if tx.send((request, body)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        for (request, body) in rx {
+            let reply = match body {
+                Ok(body) => {
+                    classroom.handle(inter, request.method().as_str(), request.url(), &body)
+                }
+                Err(e) => Reply::error(400, &format!("Cannot read the request: {}", e)),
+            };
+
+            let response = Response::from_string(reply.body.to_string())
+                .with_status_code(reply.status)
+                .with_header(json_header());
+            // The student may have gone away; that only ends their request.
+            let _ = request.respond(response);
+        }
+    });
+}
+
+fn read_body(request: &mut Request) -> io::Result<String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY)
+        .read_to_string(&mut body)?;
+    Ok(body)
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json")
+        .expect("a header made of ASCII text is valid")
+}
+
+/// The status and JSON body to reply with.
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Reply {
+    fn ok(body: impl Serialize) -> Self {
+        Self::with_status(200, body)
+    }
+
+    fn with_status(status: u16, body: impl Serialize) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => Reply { status, body },
+            Err(e) => Reply::error(500, &e.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Reply {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+}
+
+/// The quiz being served and the sessions taking it. Sessions are dropped
+/// once they have had no requests for a while, see [FINISHED_SESSION] and
+/// [IDLE_SESSION].
+struct Classroom {
+    questions: Vec<Question>,
+    options: QuizOptions,
+    sessions: HashMap<String, Session>,
+}
+
+/// A student taking the quiz.
+struct Session {
+    name: String,
+    /// The questions in the order asked.
+    questions: Vec<AskedQuestion>,
+    answered: usize,
+    score: f64,
+    /// When the session last had a request.
+    last_seen: SystemTime,
+}
+
+/// A question with its choices in the order shown to the student.
+struct AskedQuestion {
+    question: Question,
+    choices: Vec<String>,
+    correct_label: String,
+}
+
+#[derive(Deserialize)]
+struct NewSession {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AnswerBody {
+    answer: String,
+}
+
+#[derive(Serialize)]
+struct SessionView<'a> {
+    session: &'a str,
+    name: &'a str,
+    total: usize,
+}
+
+#[derive(Serialize)]
+struct QuestionView<'a> {
+    /// Counting from 1.
+    number: usize,
+    total: usize,
+    text: &'a str,
+    kind: String,
+    /// Empty if the answer is typed.
+    choices: Vec<ChoiceView<'a>>,
+    /// Whether several choices can be picked, separated by commas.
+    multi: bool,
+}
+
+#[derive(Serialize)]
+struct ChoiceView<'a> {
+    label: String,
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct AnswerView {
+    points: f64,
+    correct: bool,
+    correct_answer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+    #[serde(flatten)]
+    score: ScoreView,
+}
+
+#[derive(Serialize)]
+struct ScoreView {
+    score: f64,
+    answered: usize,
+    total: usize,
+    finished: bool,
+}
+
+impl Session {
+    fn current(&self) -> Option<&AskedQuestion> {
+        self.questions.get(self.answered)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.answered == self.questions.len()
+    }
+
+    fn score_view(&self) -> ScoreView {
+        ScoreView {
+            score: self.score,
+            answered: self.answered,
+            total: self.questions.len(),
+            finished: self.is_finished(),
+        }
+    }
+
+    /// Whether the session has had no requests for long enough at `now` to be
+    /// dropped.
+    fn is_expired(&self, now: SystemTime) -> bool {
+        let keep = if self.is_finished() {
+            FINISHED_SESSION
+        } else {
+            IDLE_SESSION
+        };
+        now.duration_since(self.last_seen)
+            .is_ok_and(|idle| idle >= keep)
+    }
+}
+
+impl Classroom {
+    fn new(questions: Vec<Question>, options: QuizOptions) -> Self {
+        Self {
+            questions,
+            options,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Answers a request for `url` with the given `method` and `body`.
+    fn handle(&mut self, inter: &mut dyn Interface, method: &str, url: &str, body: &str) -> Reply {
+        let path = url.split('?').next().unwrap_or_default();
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        let now = inter.now();
+        self.sessions.retain(|_, s| !s.is_expired(now));
+
+        match (method, parts.as_slice()) {
+            ("POST", ["sessions"]) => self.start_session(inter, body, now),
+            ("GET", ["sessions", id, "question"]) => self.with_session(id, now, Self::question),
+            ("POST", ["sessions", id, "answer"]) => self.submit(inter, id, body, now),
+            ("GET", ["sessions", id, "score"]) => {
+                self.with_session(id, now, |s| Reply::ok(s.score_view()))
+            }
+            (_, ["sessions"] | ["sessions", _, "question" | "answer" | "score"]) => {
+                Reply::error(405, &format!("{} is not allowed here", method))
+            }
+            _ => Reply::error(404, &format!("There is nothing at {}", path)),
+        }
+    }
+
+    /// Handles a request of session `id`, made at `now`.
+    fn with_session(
+        &mut self,
+        id: &str,
+        now: SystemTime,
+        f: impl FnOnce(&mut Session) -> Reply,
+    ) -> Reply {
+        match self.sessions.get_mut(id) {
+            Some(session) => {
+                session.last_seen = now;
+                f(session)
+            }
+            None => Reply::error(404, &format!("There is no session {}", id)),
+        }
+    }
+
+    /// Answers the next question of session `id`, reporting when the
+    /// session is finished.
+    fn submit(
+        &mut self,
+        inter: &mut dyn Interface,
+        id: &str,
+        body: &str,
+        now: SystemTime,
+    ) -> Reply {
+        let answer: AnswerBody = match serde_json::from_str(body) {
+            Ok(answer) => answer,
+            Err(e) => {
+                return Reply::error(400, &format!("Expected {{\"answer\": \"...\"}}: {}", e));
+            }
+        };
+
+        let reply = self.with_session(id, now, |s| Self::answer(s, &answer.answer));
+        if reply.status == 200
+            && let Some(session) = self.sessions.get(id)
+            && session.is_finished()
+        {
+            let msg = inter.messages().format(
+                "serve-finished",
+                &[
+                    ("name", session.name.as_str().into()),
+                    ("score", fmt_points(session.score).into()),
+                    ("total", session.questions.len().into()),
+                ],
+            );
+            inter.write_stdout_line(&msg);
+        }
+        reply
+    }
+
+    /// Starts a session, choosing its questions and shuffling their choices.
+    fn start_session(&mut self, inter: &mut dyn Interface, body: &str, now: SystemTime) -> Reply {
+        let new: NewSession = if body.trim().is_empty() {
+            NewSession { name: None }
+        } else {
+            match serde_json::from_str(body) {
+                Ok(new) => new,
+                Err(e) => {
+                    return Reply::error(400, &format!("Expected {{\"name\": \"...\"}}: {}", e));
+                }
+            }
+        };
+
+        let selected = self.options.select(&self.questions, inter.rng());
+        if selected.is_empty() {
+            return Reply::error(409, "No questions match the selected tags and difficulty.");
+        }
+        let questions = selected
+            .into_iter()
+            .map(|q| {
+                let (choices, correct_label) = shown_choices(&q.kind, inter.rng());
+                AskedQuestion {
+                    choices: choices.into_iter().map(String::from).collect(),
+                    correct_label,
+                    question: q.clone(),
+                }
+            })
+            .collect();
+
+        // Not drawn from the interface's generator, which `--seed` makes
+        // predictable, so that no one can guess another student's session.
+        let id = loop {
+            let id = format!("{:016x}", rand::rng().next_u64());
+            if !self.sessions.contains_key(&id) {
+                break id;
+            }
+        };
+        let name = new
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| {
+                inter
+                    .messages()
+                    .format("serve-unnamed", &[("id", id.as_str().into())])
+            });
+        let msg = inter
+            .messages()
+            .format("serve-joined", &[("name", name.as_str().into())]);
+        inter.write_stdout_line(&msg);
+
+        let session = Session {
+            name,
+            questions,
+            answered: 0,
+            score: 0.0,
+            last_seen: now,
+        };
+        let reply = Reply::with_status(
+            201,
+            SessionView {
+                session: &id,
+                name: &session.name,
+                total: session.questions.len(),
+            },
+        );
+        self.sessions.insert(id, session);
+        reply
+    }
+
+    /// Shows the session's next question, or its score once it is finished.
+    fn question(session: &mut Session) -> Reply {
+        let Some(asked) = session.current() else {
+            return Reply::ok(session.score_view());
+        };
+        let q = &asked.question;
+        Reply::ok(QuestionView {
+            number: session.answered + 1,
+            total: session.questions.len(),
+            text: &q.text,
+            kind: q.kind.kind().to_string(),
+            choices: asked
+                .choices
+                .iter()
+                .enumerate()
+                .map(|(idx, text)| ChoiceView {
+                    label: choice_label(idx),
+                    text,
+                })
+                .collect(),
+            multi: q.kind.kind() == Kind::MultiSelect,
+        })
+    }
+
+    /// Scores an answer to the session's next question and moves on.
+    fn answer(session: &mut Session, ans: &str) -> Reply {
+        let Some(asked) = session.current() else {
+            return Reply::error(409, "Every question has been answered already.");
+        };
+        let choices: Vec<&str> = asked.choices.iter().map(String::as_str).collect();
+        let points = score_answer(&asked.question.kind, &choices, &asked.correct_label, ans);
+        let correct_answer = asked.question.kind.expected();
+        let explanation = asked.question.explanation.clone();
+        let reference = asked.question.reference.clone();
+
+        session.answered += 1;
+        session.score += points;
+        Reply::ok(AnswerView {
+            points,
+            correct: points >= 1.0,
+            correct_answer,
+            explanation,
+            reference,
+            score: session.score_view(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::thread;
+
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+    use crate::schema::parse_quiz;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":2,"questions":[
+        {"text":"2 + 2?","kind":"multiple_choice","correct":"4","incorrects":["3","5"]},
+        {"text":"Capital of France?","kind":"free_text","accepted":["Paris"],
+         "explanation":"Lyon is the third largest city."}
+    ]}"#;
+
+    fn classroom() -> Classroom {
+        Classroom::new(parse_quiz(QUIZ).unwrap().questions, QuizOptions::default())
+    }
+
+    fn start(classroom: &mut Classroom, inter: &mut FakeInterface, name: &str) -> String {
+        let reply = classroom.handle(
+            inter,
+            "POST",
+            "/sessions",
+            &json!({ "name": name }).to_string(),
+        );
+        assert_eq!(reply.status, 201);
+        reply.body["session"].as_str().unwrap().to_string()
+    }
+
+    fn answer(classroom: &mut Classroom, inter: &mut FakeInterface, id: &str, ans: &str) -> Reply {
+        classroom.handle(
+            inter,
+            "POST",
+            &format!("/sessions/{}/answer", id),
+            &json!({ "answer": ans }).to_string(),
+        )
+    }
+
+    #[test]
+    fn session_answers_each_question_in_turn() {
+        let mut inter = FakeInterface::new();
+        let mut classroom = classroom();
+        let id = start(&mut classroom, &mut inter, "alice");
+
+        let question =
+            classroom.handle(&mut inter, "GET", &format!("/sessions/{}/question", id), "");
+        assert_eq!(question.body["number"], 1);
+        assert_eq!(question.body["total"], 2);
+        assert_eq!(question.body["text"], "2 + 2?");
+        assert_eq!(question.body["choices"].as_array().unwrap().len(), 3);
+
+        let reply = answer(&mut classroom, &mut inter, &id, "4");
+        assert_eq!(reply.body["correct"], true);
+        assert_eq!(reply.body["score"], 1.0);
+
+        let question =
+            classroom.handle(&mut inter, "GET", &format!("/sessions/{}/question", id), "");
+        assert_eq!(question.body["kind"], "free-text");
+        assert_eq!(question.body["choices"], json!([]));
+
+        let reply = answer(&mut classroom, &mut inter, &id, "Lyon");
+        assert_eq!(reply.body["correct"], false);
+        assert_eq!(reply.body["correct_answer"], "Paris");
+        assert_eq!(reply.body["explanation"], "Lyon is the third largest city.");
+        assert_eq!(reply.body["finished"], true);
+
+        let reply = answer(&mut classroom, &mut inter, &id, "Paris");
+        assert_eq!(reply.status, 409);
+
+        let score = classroom.handle(&mut inter, "GET", &format!("/sessions/{}/score", id), "");
+        assert_eq!(
+            score.body,
+            json!({ "score": 1.0, "answered": 2, "total": 2, "finished": true })
+        );
+        assert_eq!(
+            inter.stdout(),
+            "alice started the quiz.\nalice finished with 1 out of 2 points.\n"
+        );
+    }
+
+    #[test]
+    fn sessions_are_scored_separately() {
+        let mut inter = FakeInterface::new();
+        let mut classroom = classroom();
+        let alice = start(&mut classroom, &mut inter, "alice");
+        let bob = start(&mut classroom, &mut inter, "bob");
+        assert_ne!(alice, bob);
+
+        answer(&mut classroom, &mut inter, &alice, "4");
+        answer(&mut classroom, &mut inter, &bob, "3");
+
+        let score = |classroom: &mut Classroom, inter: &mut FakeInterface, id: &str| {
+            classroom
+                .handle(inter, "GET", &format!("/sessions/{}/score", id), "")
+                .body["score"]
+                .clone()
+        };
+        assert_eq!(score(&mut classroom, &mut inter, &alice), 1.0);
+        assert_eq!(score(&mut classroom, &mut inter, &bob), 0.0);
+    }
+
+    #[test]
+    fn finished_and_idle_sessions_expire() {
+        let mut inter = FakeInterface::new();
+        let mut classroom = classroom();
+        let alice = start(&mut classroom, &mut inter, "alice");
+        let bob = start(&mut classroom, &mut inter, "bob");
+        answer(&mut classroom, &mut inter, &alice, "4");
+        answer(&mut classroom, &mut inter, &alice, "Paris");
+
+        let score = |classroom: &mut Classroom, after: Duration, id: &str| {
+            let mut later = FakeInterface::new().with_time(SystemTime::UNIX_EPOCH + after);
+            classroom
+                .handle(&mut later, "GET", &format!("/sessions/{}/score", id), "")
+                .status
+        };
+        assert_eq!(score(&mut classroom, FINISHED_SESSION / 2, &alice), 200);
+        assert_eq!(score(&mut classroom, FINISHED_SESSION, &bob), 200);
+        assert_eq!(score(&mut classroom, FINISHED_SESSION * 2, &alice), 404);
+        assert_eq!(
+            score(&mut classroom, FINISHED_SESSION + IDLE_SESSION / 2, &bob),
+            200
+        );
+        assert_eq!(
+            score(
+                &mut classroom,
+                FINISHED_SESSION + IDLE_SESSION * 3 / 2,
+                &bob
+            ),
+            404
+        );
+    }
+
+    #[test]
+    fn session_ids_do_not_follow_the_seed() {
+        let first = start(&mut classroom(), &mut FakeInterface::new(), "alice");
+        let second = start(&mut classroom(), &mut FakeInterface::new(), "alice");
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn bad_requests_are_reported() {
+        let mut inter = FakeInterface::new();
+        let mut classroom = classroom();
+        let id = start(&mut classroom, &mut inter, "");
+
+        let cases = [
+            ("GET", "/sessions/nobody/score".to_string(), "", 404),
+            ("GET", "/nowhere".to_string(), "", 404),
+            ("DELETE", "/sessions".to_string(), "", 405),
+            ("POST", format!("/sessions/{}/answer", id), "4", 400),
+            ("POST", "/sessions".to_string(), "{", 400),
+        ];
+        for (method, url, body, status) in cases {
+            let reply = classroom.handle(&mut inter, method, &url, body);
+            assert_eq!(reply.status, status, "{} {}", method, url);
+            assert!(reply.body["error"].is_string());
+        }
+        assert_eq!(
+            inter.stdout(),
+            format!("Session {} started the quiz.\n", id)
+        );
+    }
+
+    /// Sends a request to the server on `port` and returns the status and body.
+    #[test]
+    fn failed_output_is_an_error() {
+        let mut inter = FakeInterface::new().with_stdout_error(std::io::ErrorKind::BrokenPipe);
+        let mut store = MemoryStore::new();
+
+        let err = serve(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            "127.0.0.1",
+            0,
+            QuizOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, QuizError::Io(_)), "{}", err);
+    }
+
+    fn request(port: u16, method: &str, path: &str, body: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn serves_over_http() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let handle = thread::spawn(move || {
+            let mut inter = FakeInterface::new();
+            respond(
+                &mut inter,
+                &mut classroom(),
+                server.incoming_requests().take(3),
+            );
+        });
+
+        let (status, session) = request(port, "POST", "/sessions", r#"{"name":"carol"}"#);
+        assert_eq!(status, 201);
+        let id = session["session"].as_str().unwrap();
+
+        let (status, question) = request(port, "GET", &format!("/sessions/{}/question", id), "");
+        assert_eq!(status, 200);
+        assert_eq!(question["text"], "2 + 2?");
+
+        let (status, reply) = request(
+            port,
+            "POST",
+            &format!("/sessions/{}/answer", id),
+            r#"{"answer":"4"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(reply["correct"], true);
+
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn stalled_request_does_not_hold_up_others() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let handle = thread::spawn(move || {
+            let mut inter = FakeInterface::new();
+            respond(
+                &mut inter,
+                &mut classroom(),
+                server.incoming_requests().take(2),
+            );
+        });
+
+        // tiny_http reads bodies of up to 1 KiB itself before passing the
+        // request on, so the stalled body must be larger than that.
+        let mut stalled = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(
+            stalled,
+            "POST /sessions HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2000\r\n\r\n{{"
+        )
+        .unwrap();
+
+        let (status, session) = request(port, "POST", "/sessions", r#"{"name":"dave"}"#);
+        assert_eq!(status, 201);
+        assert_eq!(session["name"], "dave");
+
+        drop(stalled);
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+//! Keeps quizzes in an SQLite database, so that a class can share one file
+//! instead of a directory of quizzes.
+
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+
+use crate::store::{QuizStore, quiz_files_in};
+
+/// Keeps each quiz as a row of the `quizzes` table, named by the path it
+/// would have as a file.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if it does not exist yet.
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database that only lasts as long as the store.
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS quizzes (
+                name TEXT PRIMARY KEY,
+                contents TEXT NOT NULL
+            )",
+        )?;
+        Ok(Self { conn })
+    }
+}
+
+/// Reads the quiz named `quiz`, or an empty string if there is none.
+fn read(conn: &Connection, quiz: &str) -> rusqlite::Result<String> {
+    let contents = conn
+        .query_row(
+            "SELECT contents FROM quizzes WHERE name = ?1",
+            params![quiz],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(contents.unwrap_or_default())
+}
+
+fn write(conn: &Connection, quiz: &str, text: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO quizzes (name, contents) VALUES (?1, ?2)
+         ON CONFLICT (name) DO UPDATE SET contents = excluded.contents",
+        params![quiz, text],
+    )?;
+    Ok(())
+}
+
+impl QuizStore for SqliteStore {
+    fn read_quiz_json(&mut self, quiz: &str) -> anyhow::Result<String> {
+        Ok(read(&self.conn, quiz)?)
+    }
+
+    fn write_quiz_json(&mut self, quiz: &str, text: &str) -> anyhow::Result<()> {
+        Ok(write(&self.conn, quiz, text)?)
+    }
+
+    /// Reads, updates and writes the quiz in one transaction, which takes the
+    /// database's write lock before reading, so other quizzers wait for it.
+    fn update_quiz_json(
+        &mut self,
+        quiz: &str,
+        update: &mut dyn FnMut(&str) -> anyhow::Result<String>,
+    ) -> anyhow::Result<()> {
+        // Rolled back when dropped, if `update` fails.
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let contents = read(&tx, quiz)?;
+        let text = update(&contents)?;
+        write(&tx, quiz, &text)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Lists the quizzes named like `.json` files directly inside `dir`.
+    fn list_quiz_files(&mut self, dir: &str) -> anyhow::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT name FROM quizzes")?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(quiz_files_in(dir, names.iter().map(String::as_str)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+    use crate::add::{AnswerArgs, add_question};
+    use crate::fake_interface::FakeInterface;
+    use crate::quiz::{DEFAULT_QUIZ_FILE, QuizOptions, run_quiz};
+    use crate::stats::load_history;
+
+    #[test]
+    fn questions_are_added_and_asked_from_the_database() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let answers = AnswerArgs {
+            correct: Some("4"),
+            incorrects: &["5".to_string()],
+            ..Default::default()
+        };
+        add_question(
+            &mut FakeInterface::new(),
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            "2 + 2?",
+            &answers,
+            &[],
+            None,
+        )
+        .unwrap();
+
+        let mut inter = FakeInterface::with_stdin("4\n");
+        run_quiz(
+            &mut inter,
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &QuizOptions::default(),
+        )
+        .unwrap();
+
+        assert!(inter.stdout().ends_with("You scored 1 out of 1 points.\n"));
+        let history = load_history(&mut store, DEFAULT_QUIZ_FILE).unwrap();
+        assert_eq!(history.runs.len(), 1);
+    }
+
+    #[test]
+    fn quizzes_are_kept_by_name_across_connections() {
+        let path = env::temp_dir().join(format!("quizzer-store-{}.db", process::id()));
+        let path = path.to_string_lossy().into_owned();
+
+        let mut store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.read_quiz_json("quiz.json").unwrap(), "");
+        store.write_quiz_json("quiz.json", "old").unwrap();
+        store.write_quiz_json("quiz.json", "new").unwrap();
+        store.write_quiz_json("maths/sums.json", "1 + 1").unwrap();
+        store.write_quiz_json("quiz.json.history", "runs").unwrap();
+        drop(store);
+
+        let mut store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.read_quiz_json("quiz.json").unwrap(), "new");
+        assert_eq!(store.list_quiz_files(".").unwrap(), ["quiz.json"]);
+        assert_eq!(
+            store.list_quiz_files("maths/").unwrap(),
+            ["maths/sums.json"]
+        );
+        drop(store);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_update_keeps_quiz() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store.write_quiz_json("quiz.json", "1").unwrap();
+
+        store
+            .update_quiz_json("quiz.json", &mut |contents| Ok(format!("{}2", contents)))
+            .unwrap();
+        let res = store.update_quiz_json("quiz.json", &mut |_| Err(anyhow::anyhow!("invalid")));
+
+        assert!(res.is_err());
+        assert_eq!(store.read_quiz_json("quiz.json").unwrap(), "12");
+    }
+}
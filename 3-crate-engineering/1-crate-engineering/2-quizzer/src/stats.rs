@@ -0,0 +1,441 @@
+//! The history of quiz runs and the statistics drawn from it.
+//!
+//! Every run of a quiz is appended to a history kept next to the quiz file,
+//! so that authors can see which questions are missed most often or take
+//! the longest, which are often the ambiguous ones.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{QuizError, check_io},
+    interface::Interface,
+    quiz::fmt_points,
+    results::QuizResults,
+    store::QuizStore,
+};
+
+/// How many of the latest runs are shown in the score trend.
+const TREND_RUNS: usize = 10;
+
+/// How many of the most missed questions are shown.
+const MOST_MISSED: usize = 3;
+
+/// The width of the bars in the score trend.
+const BAR_WIDTH: usize = 10;
+
+/// Returns where the run history of `quiz_file` is stored.
+pub fn history_file(quiz_file: &str) -> String {
+    format!("{}.history", quiz_file)
+}
+
+/// Every run of a quiz, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub runs: Vec<Run>,
+}
+
+/// A single run of a quiz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    /// When the run started, in seconds since the Unix epoch.
+    pub at: u64,
+    pub score: f64,
+    pub total: usize,
+    pub questions: Vec<QuestionRecord>,
+}
+
+/// How a question was answered in a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionRecord {
+    pub question: String,
+    pub points: f64,
+    pub correct: bool,
+    /// How long answering took, or `None` if the question was not asked.
+    pub seconds: Option<f64>,
+}
+
+impl Run {
+    /// Records the `results` of a run started at `at`.
+    pub fn new(at: u64, results: &QuizResults) -> Self {
+        Self {
+            at,
+            score: results.score,
+            total: results.total,
+            questions: results
+                .questions
+                .iter()
+                .map(|q| QuestionRecord {
+                    question: q.question.clone(),
+                    points: q.points,
+                    correct: q.correct,
+                    seconds: q.seconds,
+                })
+                .collect(),
+        }
+    }
+
+    /// The share of the points scored, from 0 to 1.
+    fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.score / self.total as f64
+        }
+    }
+}
+
+/// Appends a run to the history of `quiz_file`. Runs saved meanwhile by
+/// another quizzer are kept.
+pub fn record_run(store: &mut dyn QuizStore, quiz_file: &str, run: &Run) -> anyhow::Result<()> {
+    store.update_quiz_json(&history_file(quiz_file), &mut |contents| {
+        let mut history = parse_history(contents)?;
+        history.runs.push(run.clone());
+        Ok(serde_json::to_string(&history)?)
+    })
+}
+
+/// Loads the history of `quiz_file`, or an empty one if it was never run.
+pub fn load_history(store: &mut dyn QuizStore, quiz_file: &str) -> anyhow::Result<History> {
+    let contents = store.read_quiz_json(&history_file(quiz_file))?;
+    parse_history(&contents)
+}
+
+fn parse_history(contents: &str) -> anyhow::Result<History> {
+    if contents.is_empty() {
+        Ok(History::default())
+    } else {
+        Ok(serde_json::from_str(contents)?)
+    }
+}
+
+/// How a single question fared across runs.
+#[derive(Debug, Default, PartialEq)]
+struct QuestionStats {
+    answers: usize,
+    right: usize,
+    seconds: f64,
+}
+
+impl QuestionStats {
+    fn missed(&self) -> usize {
+        self.answers - self.right
+    }
+
+    fn success_rate(&self) -> f64 {
+        self.right as f64 / self.answers as f64
+    }
+}
+
+/// Sums up how each question was answered across `runs`, in the order the
+/// questions were first asked. Questions that were not asked are left out.
+fn question_stats(runs: &[Run]) -> Vec<(&str, QuestionStats)> {
+    let mut order = Vec::new();
+    let mut stats: BTreeMap<&str, QuestionStats> = BTreeMap::new();
+    for q in runs.iter().flat_map(|run| &run.questions) {
+        let Some(seconds) = q.seconds else {
+            continue;
+        };
+        let entry = stats.entry(&q.question).or_insert_with(|| {
+            order.push(q.question.as_str());
+            QuestionStats::default()
+        });
+        entry.answers += 1;
+        entry.right += usize::from(q.correct);
+        entry.seconds += seconds;
+    }
+
+    order
+        .into_iter()
+        .map(|question| (question, stats.remove(question).unwrap_or_default()))
+        .collect()
+}
+
+/// Prints statistics on the runs of the quiz in `quiz_file`: the score
+/// trend, the average time per answer, how often each question was answered
+/// right and which questions are missed most.
+pub fn stats(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+) -> Result<(), QuizError> {
+    let history = load_history(store, quiz_file).map_err(|source| QuizError::Load {
+        file: history_file(quiz_file),
+        source,
+    })?;
+
+    if history.runs.is_empty() {
+        let msg = inter
+            .messages()
+            .format("stats-not-run", &[("file", quiz_file.into())]);
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    let runs = &history.runs;
+    let msg = inter.messages().format(
+        "stats-runs",
+        &[("file", quiz_file.into()), ("count", runs.len().into())],
+    );
+    inter.write_stdout_line(&format!("{}\n", msg));
+
+    let shown = &runs[runs.len().saturating_sub(TREND_RUNS)..];
+    let msg = if shown.len() < runs.len() {
+        inter
+            .messages()
+            .format("stats-last-scores", &[("count", shown.len().into())])
+    } else {
+        inter.messages().get("stats-scores")
+    };
+    inter.write_stdout_line(&msg);
+    for run in shown {
+        let msg = inter.messages().format(
+            "stats-score",
+            &[
+                ("score", fmt_points(run.score).into()),
+                ("total", run.total.into()),
+            ],
+        );
+        inter.write_stdout_line(&format!(
+            "  {}  {}  {}",
+            fmt_date(run.at),
+            fmt_bar(run.ratio()),
+            msg
+        ));
+    }
+
+    let average = runs.iter().map(Run::ratio).sum::<f64>() / runs.len() as f64;
+    let first = runs.first().map_or(0.0, Run::ratio);
+    let last = runs.last().map_or(0.0, Run::ratio);
+    let msg = inter.messages().format(
+        "stats-average",
+        &[
+            ("average", fmt_percent(average).into()),
+            ("first", fmt_percent(first).into()),
+            ("last", fmt_percent(last).into()),
+        ],
+    );
+    inter.write_stdout_line(&msg);
+
+    let questions = question_stats(runs);
+    let answers: usize = questions.iter().map(|(_, s)| s.answers).sum();
+    if answers == 0 {
+        return check_io(inter);
+    }
+    let seconds: f64 = questions.iter().map(|(_, s)| s.seconds).sum();
+    let msg = inter.messages().format(
+        "stats-average-time",
+        &[("seconds", fmt_seconds(seconds / answers as f64).into())],
+    );
+    inter.write_stdout_line(&format!("{}\n", msg));
+
+    let msg = inter.messages().get("stats-answered-right");
+    inter.write_stdout_line(&msg);
+    for (question, s) in &questions {
+        let msg = inter.messages().format(
+            "stats-question",
+            &[
+                ("question", (*question).into()),
+                ("right", s.right.into()),
+                ("answers", s.answers.into()),
+                ("rate", fmt_percent(s.success_rate()).into()),
+                ("seconds", fmt_seconds(s.seconds / s.answers as f64).into()),
+            ],
+        );
+        inter.write_stdout_line(&format!("  {}", msg));
+    }
+
+    let mut missed: Vec<_> = questions.iter().filter(|(_, s)| s.missed() > 0).collect();
+    // Stable, so questions missed as often stay in the order they were asked.
+    missed.sort_by(|(_, a), (_, b)| {
+        b.missed()
+            .cmp(&a.missed())
+            .then(a.success_rate().total_cmp(&b.success_rate()))
+    });
+    if !missed.is_empty() {
+        let msg = inter.messages().get("stats-most-missed");
+        inter.write_stdout_line(&format!("\n{}", msg));
+        for (idx, (question, s)) in missed.iter().take(MOST_MISSED).enumerate() {
+            let msg = inter.messages().format(
+                "stats-missed",
+                &[
+                    ("number", (idx + 1).into()),
+                    ("question", (**question).into()),
+                    ("missed", s.missed().into()),
+                    ("answers", s.answers.into()),
+                ],
+            );
+            inter.write_stdout_line(&format!("  {}", msg));
+        }
+    }
+
+    check_io(inter)
+}
+
+fn fmt_seconds(seconds: f64) -> String {
+    format!("{:.1}", seconds)
+}
+
+fn fmt_percent(ratio: f64) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+/// Draws `ratio` as a bar of `#`s.
+fn fmt_bar(ratio: f64) -> String {
+    let filled = ((ratio * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    format!("[{}{}]", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+fn fmt_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let (hour, minute) = (secs % 86_400 / 3600, secs % 3600 / 60);
+
+    // Converts days since the epoch to a civil date, following Howard
+    // Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year, month, day, hour, minute
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_interface::FakeInterface;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+
+    use super::*;
+
+    fn record(question: &str, correct: bool, seconds: Option<f64>) -> QuestionRecord {
+        QuestionRecord {
+            question: question.to_string(),
+            points: if correct { 1.0 } else { 0.0 },
+            correct,
+            seconds,
+        }
+    }
+
+    fn run(at: u64, questions: Vec<QuestionRecord>) -> Run {
+        Run {
+            at,
+            score: questions.iter().map(|q| q.points).sum(),
+            total: questions.len(),
+            questions,
+        }
+    }
+
+    fn history_of(runs: Vec<Run>) -> String {
+        serde_json::to_string(&History { runs }).unwrap()
+    }
+
+    #[test]
+    fn record_run_appends_to_history() {
+        let mut store = MemoryStore::new().with_file(
+            &history_file(DEFAULT_QUIZ_FILE),
+            &history_of(vec![run(1, vec![record("One?", true, Some(2.0))])]),
+        );
+
+        record_run(
+            &mut store,
+            DEFAULT_QUIZ_FILE,
+            &run(2, vec![record("One?", false, Some(4.0))]),
+        )
+        .unwrap();
+
+        let history = load_history(&mut store, DEFAULT_QUIZ_FILE).unwrap();
+        let times: Vec<u64> = history.runs.iter().map(|r| r.at).collect();
+        assert_eq!(times, [1, 2]);
+    }
+
+    #[test]
+    fn stats_show_trend_success_rates_and_most_missed() {
+        let day = 86_400;
+        let start = 1_700_000_000;
+        let runs = vec![
+            run(
+                start,
+                vec![
+                    record("One?", true, Some(2.0)),
+                    record("Two?", false, Some(9.0)),
+                    record("Three?", false, None),
+                ],
+            ),
+            run(
+                start + day,
+                vec![
+                    record("Two?", false, Some(7.0)),
+                    record("One?", true, Some(1.0)),
+                    record("Three?", false, Some(3.0)),
+                ],
+            ),
+            run(
+                start + 2 * day,
+                vec![
+                    record("One?", true, Some(3.0)),
+                    record("Two?", true, Some(5.0)),
+                    record("Three?", true, Some(2.0)),
+                ],
+            ),
+        ];
+        let mut inter = FakeInterface::new();
+        let mut store =
+            MemoryStore::new().with_file(&history_file(DEFAULT_QUIZ_FILE), &history_of(runs));
+
+        stats(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            quiz.json was run 3 times.\n\
+            \n\
+            Scores:\n  \
+              2023-11-14 22:13  [###       ]  1 out of 3 points\n  \
+              2023-11-15 22:13  [###       ]  1 out of 3 points\n  \
+              2023-11-16 22:13  [##########]  3 out of 3 points\n\
+            Average score: 56%, from 33% in the first run to 100% in the last.\n\
+            Average time per answer: 4.0s\n\
+            \n\
+            Answered right:\n  \
+              One?: 3 of 3 (100%), 2.0s on average\n  \
+              Two?: 1 of 3 (33%), 7.0s on average\n  \
+              Three?: 1 of 2 (50%), 2.5s on average\n\
+            \n\
+            Most missed:\n  \
+              1. Two? (missed 2 of 3 times)\n  \
+              2. Three? (missed 1 of 2 times)\n\
+            "
+        );
+    }
+
+    #[test]
+    fn stats_without_runs_say_so() {
+        let mut inter = FakeInterface::new();
+        let mut store = MemoryStore::new();
+
+        stats(&mut inter, &mut store, DEFAULT_QUIZ_FILE).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "quiz.json has not been run yet.\nRun it with ./quizzer quiz\n"
+        );
+    }
+
+    #[test]
+    fn dates_are_formatted_in_utc() {
+        assert_eq!(fmt_date(0), "1970-01-01 00:00");
+        assert_eq!(fmt_date(951_782_400 + 3_661), "2000-02-29 01:01");
+        assert_eq!(fmt_date(1_700_000_000), "2023-11-14 22:13");
+    }
+}
@@ -0,0 +1,57 @@
+//! Where quizzes, and the study logs and histories kept beside them, are stored.
+//!
+//! Every stored item is JSON text under a name, such as `quiz.json` or
+//! `quiz.json.history`, whether it is kept as a file, in memory or in a
+//! database. Other files, such as questions to import, answer sheets and
+//! the results of a quiz run, are read and written through the store too, but
+//! kept as files outside of it.
+
+use std::fs;
+
+pub trait QuizStore {
+    /// Reads the quiz stored at `quiz`, or an empty string if it does not exist yet.
+    fn read_quiz_json(&mut self, quiz: &str) -> anyhow::Result<String>;
+    fn write_quiz_json(&mut self, quiz: &str, text: &str) -> anyhow::Result<()>;
+    /// Replaces the quiz stored at `quiz` with what `update` makes of its
+    /// current contents, which are empty if it does not exist yet. No other
+    /// quizzer can change the quiz in between, so changes saved meanwhile by
+    /// another session are passed to `update` rather than overwritten.
+    fn update_quiz_json(
+        &mut self,
+        quiz: &str,
+        update: &mut dyn FnMut(&str) -> anyhow::Result<String>,
+    ) -> anyhow::Result<()>;
+    /// Lists the identifiers of the quiz files found in `dir`, sorted by name.
+    fn list_quiz_files(&mut self, dir: &str) -> anyhow::Result<Vec<String>>;
+    /// Reads any other file, such as questions to import.
+    fn read_file(&mut self, path: &str) -> anyhow::Result<String> {
+        Ok(fs::read_to_string(path)?)
+    }
+    /// Writes any other file, such as the results of a quiz run.
+    fn write_file(&mut self, path: &str, text: &str) -> anyhow::Result<()> {
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+/// Picks the names of the `.json` files directly inside `dir` out of `names`,
+/// for stores that keep quizzes by name rather than in a directory.
+pub(crate) fn quiz_files_in<'a>(
+    dir: &str,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let prefix = match dir {
+        "." | "" => String::new(),
+        _ => format!("{}/", dir.trim_end_matches('/')),
+    };
+    let mut quizzes: Vec<String> = names
+        .into_iter()
+        .filter(|path| {
+            path.strip_prefix(&prefix)
+                .is_some_and(|name| !name.contains('/') && name.ends_with(".json"))
+        })
+        .map(str::to_string)
+        .collect();
+    quizzes.sort();
+    quizzes
+}
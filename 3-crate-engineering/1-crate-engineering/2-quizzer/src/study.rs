@@ -0,0 +1,463 @@
+//! Spaced-repetition study mode.
+//!
+//! Every answer is recorded in a study log kept next to the quiz file, and
+//! questions are rescheduled with a simplified SM-2 algorithm: questions
+//! answered correctly come back after longer and longer intervals, while
+//! wrong answers bring a question back the next day.
+//!
+//! The log keys each question by its text, so a question whose text is
+//! edited is studied as a new one. Its old history stays in the log, unused.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    check::warn_problems,
+    error::{QuizError, check_io},
+    i18n::Messages,
+    interface::Interface,
+    quiz::{Outcome, Question, ask_question},
+    seal::load_unlocked,
+    store::QuizStore,
+};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// The ease factor SM-2 starts every question with.
+const INITIAL_EASE: f64 = 2.5;
+
+/// The lowest ease factor SM-2 allows.
+const MIN_EASE: f64 = 1.3;
+
+/// Returns where the study log of `quiz_file` is stored.
+pub fn study_file(quiz_file: &str) -> String {
+    format!("{}.study", quiz_file)
+}
+
+/// The study history of every question in a quiz, keyed by question text.
+/// Entries of questions that were since edited or removed are kept.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StudyLog {
+    pub cards: BTreeMap<String, Card>,
+}
+
+/// The study history and schedule of a single question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Card {
+    pub attempts: Vec<Attempt>,
+    /// How quickly the interval grows after a correct answer.
+    pub ease: f64,
+    /// The number of correct answers in a row.
+    pub repetitions: u32,
+    /// The number of days until the question is due after the last attempt.
+    pub interval_days: u64,
+    /// When the question is next due, in seconds since the Unix epoch.
+    pub due: u64,
+}
+
+/// A single answer to a question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// When the question was answered, in seconds since the Unix epoch.
+    pub at: u64,
+    pub correct: bool,
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self {
+            attempts: Vec::new(),
+            ease: INITIAL_EASE,
+            repetitions: 0,
+            interval_days: 0,
+            due: 0,
+        }
+    }
+}
+
+impl Card {
+    /// Records an answer given at `now` and schedules the next review.
+    pub fn record(&mut self, now: u64, correct: bool) {
+        self.attempts.push(Attempt { at: now, correct });
+
+        // SM-2 grades answers from 0 to 5; a plain right or wrong answer is
+        // graded as a 4 or a 2.
+        let quality: f64 = if correct { 4.0 } else { 2.0 };
+        self.ease =
+            (self.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+
+        if correct {
+            self.interval_days = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval_days as f64 * self.ease).round() as u64,
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        }
+
+        self.due = now + self.interval_days * DAY_SECS;
+    }
+}
+
+impl StudyLog {
+    /// Returns the questions due at `now`: overdue questions first, most
+    /// overdue first, followed by questions never studied before in quiz order.
+    pub fn due_questions<'a>(&self, questions: &'a [Question], now: u64) -> Vec<&'a Question> {
+        let mut reviews: Vec<(u64, &Question)> = Vec::new();
+        let mut new: Vec<&Question> = Vec::new();
+
+        for q in questions {
+            match self.cards.get(&q.text) {
+                Some(card) if card.attempts.is_empty() => new.push(q),
+                Some(card) if card.due <= now => reviews.push((card.due, q)),
+                Some(_) => {}
+                None => new.push(q),
+            }
+        }
+
+        // A stable sort keeps questions due at the same time in quiz order.
+        reviews.sort_by_key(|(due, _)| *due);
+        reviews.into_iter().map(|(_, q)| q).chain(new).collect()
+    }
+
+    /// Returns when the next question of `questions` that is not due yet will be.
+    pub fn next_due(&self, questions: &[Question], now: u64) -> Option<u64> {
+        questions
+            .iter()
+            .filter_map(|q| self.cards.get(&q.text))
+            .map(|card| card.due)
+            .filter(|due| *due > now)
+            .min()
+    }
+}
+
+/// Studies the questions of the quiz in `quiz_file` that are due, recording
+/// each answer in the study log. Studies at most `limit` questions, if given,
+/// checking the answers of a quiz sealed with a key with `key`.
+pub fn study(
+    inter: &mut dyn Interface,
+    store: &mut dyn QuizStore,
+    quiz_file: &str,
+    limit: Option<usize>,
+    key: Option<&str>,
+) -> Result<(), QuizError> {
+    let quiz = load_unlocked(store, quiz_file, key)?;
+
+    if quiz.is_empty() {
+        let msg = inter.messages().get("no-questions");
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+    warn_problems(inter, quiz_file, &quiz);
+
+    let log_file = study_file(quiz_file);
+    let mut log = load_study_log(store, &log_file).map_err(|source| QuizError::Load {
+        file: log_file.clone(),
+        source,
+    })?;
+
+    let now = unix_secs(inter.now());
+    let mut due = log.due_questions(&quiz.questions, now);
+    if let Some(limit) = limit {
+        due.truncate(limit);
+    }
+
+    if due.is_empty() {
+        let messages = inter.messages();
+        let msg = match log.next_due(&quiz.questions, now) {
+            Some(next) => messages.format(
+                "study-nothing-until",
+                &[("wait", fmt_wait(messages, next - now).into())],
+            ),
+            None => messages.get("study-nothing"),
+        };
+        inter.write_stdout_line(&msg);
+        return check_io(inter);
+    }
+
+    let mut studied = 0;
+    let mut score = 0;
+
+    let total = due.len();
+    for q in due {
+        inter.show_progress(studied, total, score as f64);
+        let Some(answer) = ask_question(inter, q, None) else {
+            break;
+        };
+        let correct = answer.outcome == Outcome::Correct;
+
+        let answered_at = unix_secs(inter.now());
+        log.cards
+            .entry(q.text.clone())
+            .or_default()
+            .record(answered_at, correct);
+
+        studied += 1;
+        if correct {
+            score += 1;
+        }
+    }
+
+    let saved = save_study_log(store, &log_file, &log);
+
+    let msg = inter.messages().format(
+        "study-done",
+        &[("studied", studied.into()), ("score", score.into())],
+    );
+    inter.write_stdout_line(&msg);
+
+    saved.map_err(|source| QuizError::StudyLog {
+        file: log_file,
+        source,
+    })?;
+    check_io(inter)
+}
+
+/// Loads the study log, or an empty one if nothing was studied yet.
+pub fn load_study_log(store: &mut dyn QuizStore, log_file: &str) -> anyhow::Result<StudyLog> {
+    let contents = store.read_quiz_json(log_file)?;
+    if contents.is_empty() {
+        Ok(StudyLog::default())
+    } else {
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+pub fn save_study_log(
+    store: &mut dyn QuizStore,
+    log_file: &str,
+    log: &StudyLog,
+) -> anyhow::Result<()> {
+    let json = serde_json::to_string(log)?;
+    store.write_quiz_json(log_file, &json)
+}
+
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Formats a wait in seconds in the largest whole unit that fits.
+fn fmt_wait(messages: &Messages, secs: u64) -> String {
+    let (amount, unit) = if secs >= DAY_SECS {
+        (secs / DAY_SECS, "study-days")
+    } else if secs >= 60 * 60 {
+        (secs / (60 * 60), "study-hours")
+    } else {
+        (secs.div_ceil(60), "study-minutes")
+    };
+    messages.format(unit, &[("count", amount.into())])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_interface::FakeInterface;
+    use crate::i18n::Lang;
+    use crate::memory_store::MemoryStore;
+    use crate::quiz::DEFAULT_QUIZ_FILE;
+
+    use super::*;
+
+    const QUIZ: &str = r#"{"version":1,"questions":[
+        {"text":"One?","correct":"1","incorrects":["0"]},
+        {"text":"Two?","correct":"2","incorrects":["0"]},
+        {"text":"Three?","correct":"3","incorrects":["0"]}
+    ]}"#;
+
+    const START: u64 = 1_700_000_000;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    /// Runs a study session at `now`, continuing from the previous session's log.
+    fn session(prev: Option<&MemoryStore>, stdin: &str, now: u64) -> (FakeInterface, MemoryStore) {
+        let mut inter = FakeInterface::with_stdin(stdin).with_time(at(now));
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+        if let Some(log) = prev.and_then(|p| p.file(&study_file(DEFAULT_QUIZ_FILE))) {
+            store = store.with_file(&study_file(DEFAULT_QUIZ_FILE), log);
+        }
+
+        study(&mut inter, &mut store, DEFAULT_QUIZ_FILE, None, None).unwrap();
+        (inter, store)
+    }
+
+    fn log_of(store: &MemoryStore) -> StudyLog {
+        serde_json::from_str(store.file(&study_file(DEFAULT_QUIZ_FILE)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn sm2_intervals_grow_with_correct_answers() {
+        let mut card = Card::default();
+
+        card.record(0, true);
+        assert_eq!(card.interval_days, 1);
+        card.record(card.due, true);
+        assert_eq!(card.interval_days, 6);
+        card.record(card.due, true);
+        assert_eq!(card.interval_days, 15);
+        assert_eq!(card.due, (1 + 6 + 15) * DAY_SECS);
+    }
+
+    #[test]
+    fn sm2_wrong_answer_resets_interval_and_lowers_ease() {
+        let mut card = Card::default();
+        card.record(0, true);
+        card.record(DAY_SECS, true);
+
+        card.record(7 * DAY_SECS, false);
+
+        assert_eq!(card.repetitions, 0);
+        assert_eq!(card.interval_days, 1);
+        assert_eq!(card.due, 8 * DAY_SECS);
+        assert!(card.ease < INITIAL_EASE);
+        assert_eq!(card.attempts.len(), 3);
+    }
+
+    #[test]
+    fn ease_never_drops_below_minimum() {
+        let mut card = Card::default();
+
+        for day in 0..10 {
+            card.record(day * DAY_SECS, false);
+        }
+
+        assert_eq!(card.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn first_session_asks_every_question_and_records_attempts() {
+        let (inter, store) = session(None, "1\nwrong\n3\n", START);
+
+        assert!(
+            inter
+                .stdout()
+                .ends_with("You studied 3 questions and got 2 right.\n")
+        );
+        let log = log_of(&store);
+        assert_eq!(
+            log.cards["Two?"].attempts,
+            vec![Attempt {
+                at: START,
+                correct: false
+            }]
+        );
+        assert_eq!(log.cards["One?"].due, START + DAY_SECS);
+    }
+
+    #[test]
+    fn nothing_due_before_next_review() {
+        let (_, first) = session(None, "1\n2\n3\n", START);
+
+        let (second, _) = session(Some(&first), "", START + 60 * 60);
+
+        assert_eq!(
+            second.stdout(),
+            "\
+            Nothing to study right now. The next question is due in 23 hours.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn wait_is_told_in_dutch() {
+        let (_, first) = session(None, "1\n2\n3\n", START);
+        let mut inter = FakeInterface::new()
+            .with_time(at(START + DAY_SECS - 60))
+            .with_lang(Lang::Nl);
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ).with_file(
+            &study_file(DEFAULT_QUIZ_FILE),
+            first.file(&study_file(DEFAULT_QUIZ_FILE)).unwrap(),
+        );
+
+        study(&mut inter, &mut store, DEFAULT_QUIZ_FILE, None, None).unwrap();
+
+        assert_eq!(
+            inter.stdout(),
+            "\
+            Er valt nu niets te studeren. De volgende vraag komt over 1 minuut.\n\
+            "
+        );
+    }
+
+    #[test]
+    fn due_questions_are_most_overdue_first_then_new() {
+        let quiz = crate::schema::parse_quiz(QUIZ).unwrap();
+        let card = |due| Card {
+            attempts: vec![Attempt {
+                at: 0,
+                correct: true,
+            }],
+            due,
+            ..Card::default()
+        };
+        let log = StudyLog {
+            cards: BTreeMap::from([
+                ("One?".to_string(), card(150)),
+                ("Two?".to_string(), card(100)),
+            ]),
+        };
+
+        let due: Vec<&str> = log
+            .due_questions(&quiz.questions, 200)
+            .iter()
+            .map(|q| q.text.as_str())
+            .collect();
+
+        assert_eq!(due, vec!["Two?", "One?", "Three?"]);
+        assert_eq!(log.due_questions(&quiz.questions, 120).len(), 2);
+    }
+
+    #[test]
+    fn wrong_answers_come_back_the_next_day() {
+        let (_, first) = session(None, "1\n2\n3\n", START);
+        let (_, second) = session(Some(&first), "1\nwrong\n3\n", START + DAY_SECS);
+
+        let log = log_of(&second);
+        assert_eq!(log.cards["Two?"].repetitions, 0);
+        assert_eq!(log.cards["Two?"].interval_days, 1);
+        assert_eq!(log.cards["One?"].interval_days, 6);
+
+        let (third, third_store) = session(Some(&second), "2\n", START + 2 * DAY_SECS);
+
+        assert_eq!(
+            third.stdout(),
+            "Two?\n a) 0\n b) 2\n>>> Correct!\n\nYou studied 1 questions and got 1 right.\n"
+        );
+        assert_eq!(log_of(&third_store).cards["Two?"].attempts.len(), 3);
+    }
+
+    #[test]
+    fn limit_caps_questions_studied() {
+        let mut inter = FakeInterface::with_stdin("1\n").with_time(at(START));
+        let mut store = MemoryStore::new().with_quiz_file(QUIZ);
+
+        study(&mut inter, &mut store, DEFAULT_QUIZ_FILE, Some(1), None).unwrap();
+
+        assert!(
+            inter
+                .stdout()
+                .ends_with("You studied 1 questions and got 1 right.\n")
+        );
+        assert_eq!(log_of(&store).cards.len(), 1);
+    }
+
+    #[test]
+    fn broken_study_log_is_an_error() {
+        let mut inter = FakeInterface::with_stdin("1\n").with_time(at(START));
+        let mut store = MemoryStore::new()
+            .with_quiz_file(QUIZ)
+            .with_file(&study_file(DEFAULT_QUIZ_FILE), "not json");
+
+        let err = study(&mut inter, &mut store, DEFAULT_QUIZ_FILE, None, None).unwrap_err();
+
+        assert!(matches!(err, QuizError::Load { file, .. } if file == "quiz.json.study"));
+        assert_eq!(store.file(&study_file(DEFAULT_QUIZ_FILE)), Some("not json"));
+    }
+}
@@ -0,0 +1,495 @@
+//! A terminal user interface, where answers are picked with the arrow keys.
+
+use std::io::{self, Stdout, Write, stdout};
+use std::time::{Duration, Instant, SystemTime};
+
+use crossterm::cursor::{Hide, MoveToColumn, MoveUp, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, PrintStyledContent, SetAttribute, Stylize};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use rand::RngCore;
+
+use crate::i18n::Messages;
+use crate::interface::{Interface, Prompt, TimedLine};
+use crate::quiz::{Outcome, choice_label, fmt_points};
+use crate::real_interface::RealInterface;
+
+/// Asks questions in the terminal, letting the user pick answers with the
+/// arrow keys, and shows the progress and score. Everything but the terminal
+/// is left to a [RealInterface].
+///
+/// All input is read key by key, so stdin must be a terminal.
+pub struct TuiInterface {
+    inner: RealInterface,
+    stdout: Stdout,
+    /// The questions asked, the number of questions and the score so far.
+    progress: Option<(usize, usize, f64)>,
+    /// The first error drawing to or reading from the terminal.
+    io_error: Option<io::Error>,
+}
+
+impl TuiInterface {
+    pub fn new(inner: RealInterface) -> Self {
+        Self {
+            inner,
+            stdout: stdout(),
+            progress: None,
+            io_error: None,
+        }
+    }
+
+    /// Reads a line, echoing what is typed, until Enter is pressed.
+    fn read_line(&mut self, timeout: Option<Duration>) -> TimedLine {
+        self.run_editor(&mut LineEditor::default(), timeout)
+    }
+
+    /// Lets the user pick from `choices`, redrawing them after every key.
+    fn pick(&mut self, prompt: &Prompt, timeout: Option<Duration>) -> TimedLine {
+        let mut picker = Picker::new(prompt.choices, prompt.multi);
+        let hint = self.messages().get(if prompt.multi {
+            "quiz-hint-pick"
+        } else {
+            "quiz-hint-choose"
+        });
+        self.run_editor(
+            &mut ChoiceEditor {
+                picker: &mut picker,
+                hint: &hint,
+            },
+            timeout,
+        )
+    }
+
+    /// Feeds keys to `editor` in raw mode until it is done or time runs out.
+    fn run_editor(&mut self, editor: &mut dyn Editor, timeout: Option<Duration>) -> TimedLine {
+        match self.try_run_editor(editor, timeout) {
+            Ok(line) => line,
+            Err(e) => {
+                self.io_error.get_or_insert(e);
+                TimedLine::Closed
+            }
+        }
+    }
+
+    fn try_run_editor(
+        &mut self,
+        editor: &mut dyn Editor,
+        timeout: Option<Duration>,
+    ) -> io::Result<TimedLine> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let _raw = RawMode::enable()?;
+
+        let mut drawn = editor.draw(&mut self.stdout)?;
+        loop {
+            let Some(key) = next_key(deadline)? else {
+                execute!(self.stdout, Print("\r\n"))?;
+                return Ok(TimedLine::TimedOut);
+            };
+            let done = editor.handle(key);
+
+            // Go back to the start of what was drawn and draw it again.
+            queue!(self.stdout, MoveToColumn(0))?;
+            if drawn > 0 {
+                queue!(self.stdout, MoveUp(drawn))?;
+            }
+            queue!(self.stdout, Clear(ClearType::FromCursorDown))?;
+            drawn = editor.draw(&mut self.stdout)?;
+
+            match done {
+                KeyResult::Pending => {}
+                KeyResult::Done(line) => {
+                    execute!(self.stdout, Print("\r\n"))?;
+                    return Ok(TimedLine::Line(line));
+                }
+                KeyResult::Quit => {
+                    execute!(self.stdout, Print("\r\n"))?;
+                    return Ok(TimedLine::Closed);
+                }
+            }
+        }
+    }
+}
+
+impl Interface for TuiInterface {
+    fn read_stdin_line(&mut self) -> Option<String> {
+        match self.read_line(None) {
+            TimedLine::Line(line) => Some(line),
+            TimedLine::TimedOut | TimedLine::Closed => None,
+        }
+    }
+
+    fn read_stdin_line_timeout(&mut self, timeout: Duration) -> TimedLine {
+        self.read_line(Some(timeout))
+    }
+
+    fn write_stdout(&mut self, text: &str) {
+        self.inner.write_stdout(text);
+    }
+
+    fn write_stdout_line(&mut self, text: &str) {
+        self.inner.write_stdout_line(text);
+    }
+
+    /// Shows the progress and the question, then lets the user pick an answer
+    /// with the arrow keys, or type it if the question has no choices.
+    fn ask(&mut self, prompt: &Prompt, timeout: Option<Duration>) -> Option<TimedLine> {
+        if let Some((asked, total, score)) = self.progress.take() {
+            let progress = self.messages().format(
+                "quiz-progress",
+                &[
+                    ("number", (asked + 1).into()),
+                    ("total", total.into()),
+                    ("score", fmt_points(score).into()),
+                ],
+            );
+            self.write_stdout_line(&progress.dim().to_string());
+        }
+        self.write_stdout_line(&prompt.text.bold().to_string());
+
+        Some(if prompt.choices.is_empty() {
+            self.read_line(timeout)
+        } else {
+            self.pick(prompt, timeout)
+        })
+    }
+
+    /// Shows right answers in green, partly right ones in yellow and wrong
+    /// ones in red.
+    fn show_feedback(&mut self, outcome: Outcome, message: &str) {
+        let styled = match outcome {
+            Outcome::Correct => message.green(),
+            Outcome::Partial => message.yellow(),
+            Outcome::Wrong | Outcome::TimedOut => message.red(),
+        };
+        self.write_stdout_line(&styled.bold().to_string());
+    }
+
+    fn show_progress(&mut self, asked: usize, total: usize, score: f64) {
+        self.progress = Some((asked, total, score));
+    }
+
+    fn take_io_error(&mut self) -> Option<io::Error> {
+        self.io_error.take().or_else(|| self.inner.take_io_error())
+    }
+
+    fn now(&mut self) -> SystemTime {
+        self.inner.now()
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        self.inner.rng()
+    }
+
+    fn messages(&self) -> &Messages {
+        self.inner.messages()
+    }
+}
+
+/// Keeps the terminal in raw mode, with the cursor hidden, until dropped.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // Nothing more can be done if the terminal cannot be restored.
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(stdout(), Show);
+    }
+}
+
+/// Waits for the next key press, or returns `None` once `deadline` passes.
+fn next_key(deadline: Option<Instant>) -> io::Result<Option<KeyEvent>> {
+    loop {
+        if let Some(deadline) = deadline {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if !event::poll(left)? {
+                return Ok(None);
+            }
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            return Ok(Some(key));
+        }
+    }
+}
+
+/// What pressing a key did.
+#[derive(Debug, PartialEq, Eq)]
+enum KeyResult {
+    Pending,
+    /// The answer was entered.
+    Done(String),
+    /// The user asked to stop, which ends the input.
+    Quit,
+}
+
+/// Returns true for the keys that end the input: Escape, Ctrl-C and Ctrl-D.
+fn is_quit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Esc
+        || (key.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('d')))
+}
+
+/// Something edited key by key and drawn in raw mode.
+trait Editor {
+    fn handle(&mut self, key: KeyEvent) -> KeyResult;
+    /// Draws the editor from the start of the current line, returning how
+    /// many lines down the cursor ended up.
+    fn draw(&self, out: &mut Stdout) -> io::Result<u16>;
+}
+
+/// A line of typed text.
+#[derive(Debug, Default)]
+struct LineEditor {
+    line: String,
+}
+
+impl Editor for LineEditor {
+    fn handle(&mut self, key: KeyEvent) -> KeyResult {
+        if is_quit(&key) {
+            return KeyResult::Quit;
+        }
+        match key.code {
+            KeyCode::Enter => return KeyResult::Done(self.line.clone()),
+            KeyCode::Backspace => {
+                self.line.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.line.push(c),
+            _ => {}
+        }
+        KeyResult::Pending
+    }
+
+    fn draw(&self, out: &mut Stdout) -> io::Result<u16> {
+        queue!(out, Print(">>> "), Print(&self.line), Show)?;
+        out.flush()?;
+        Ok(0)
+    }
+}
+
+/// Which of a question's choices is under the cursor, and which are picked.
+#[derive(Debug)]
+struct Picker<'a> {
+    choices: &'a [&'a str],
+    cursor: usize,
+    /// Whether each choice is picked, for multi-select questions.
+    picked: Vec<bool>,
+    multi: bool,
+}
+
+impl<'a> Picker<'a> {
+    fn new(choices: &'a [&'a str], multi: bool) -> Self {
+        Self {
+            choices,
+            cursor: 0,
+            picked: vec![false; choices.len()],
+            multi,
+        }
+    }
+
+    /// Moves the cursor with the arrow keys, or to the choice whose label is
+    /// typed, and picks choices with space. Enter answers with the text of
+    /// the picked choices, or the one under the cursor.
+    fn handle(&mut self, key: KeyEvent) -> KeyResult {
+        if is_quit(&key) {
+            return KeyResult::Quit;
+        }
+        let len = self.picked.len();
+        match key.code {
+            KeyCode::Up => self.cursor = (self.cursor + len - 1) % len,
+            KeyCode::Down | KeyCode::Tab => self.cursor = (self.cursor + 1) % len,
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len - 1,
+            KeyCode::Char(' ') if self.multi => self.picked[self.cursor] ^= true,
+            KeyCode::Char(c) => {
+                let label = c.to_lowercase().to_string();
+                // Digits pick choices by number, 1 for the first.
+                let number = c.to_digit(10).and_then(|n| (n as usize).checked_sub(1));
+                if let Some(idx) = (0..len)
+                    .find(|idx| choice_label(*idx) == label)
+                    .or(number.filter(|idx| *idx < len))
+                {
+                    self.cursor = idx;
+                    if self.multi {
+                        self.picked[idx] ^= true;
+                    }
+                }
+            }
+            KeyCode::Enter => return KeyResult::Done(self.answer()),
+            _ => {}
+        }
+        KeyResult::Pending
+    }
+
+    /// Answers with the text of the choices rather than their labels, since
+    /// a choice's text can be another choice's label.
+    fn answer(&self) -> String {
+        if !self.multi {
+            return self.choices[self.cursor].to_string();
+        }
+        let picks: Vec<&str> = (0..self.choices.len())
+            .filter(|idx| self.picked[*idx])
+            .map(|idx| self.choices[idx])
+            .collect();
+        picks.join(", ")
+    }
+
+    /// The lines showing the choices, with a marker at the cursor and boxes
+    /// for multi-select questions.
+    fn lines(&self) -> Vec<String> {
+        self.choices
+            .iter()
+            .enumerate()
+            .map(|(idx, ans)| {
+                let cursor = if idx == self.cursor { '>' } else { ' ' };
+                let label = choice_label(idx);
+                if self.multi {
+                    let check = if self.picked[idx] { 'x' } else { ' ' };
+                    format!("{} [{}] {}) {}", cursor, check, label, ans)
+                } else {
+                    format!("{} {}) {}", cursor, label, ans)
+                }
+            })
+            .collect()
+    }
+}
+
+/// A picker drawn with its choices and a hint of which keys to use.
+struct ChoiceEditor<'a, 'b> {
+    picker: &'a mut Picker<'b>,
+    hint: &'a str,
+}
+
+impl Editor for ChoiceEditor<'_, '_> {
+    fn handle(&mut self, key: KeyEvent) -> KeyResult {
+        self.picker.handle(key)
+    }
+
+    fn draw(&self, out: &mut Stdout) -> io::Result<u16> {
+        queue!(out, Hide)?;
+        for (idx, line) in self.picker.lines().into_iter().enumerate() {
+            if idx == self.picker.cursor {
+                queue!(out, PrintStyledContent(line.reverse()))?;
+            } else {
+                queue!(out, Print(line))?;
+            }
+            queue!(out, Print("\r\n"))?;
+        }
+        queue!(
+            out,
+            SetAttribute(Attribute::Dim),
+            Print(self.hint),
+            SetAttribute(Attribute::Reset)
+        )?;
+        out.flush()?;
+        Ok(u16::try_from(self.picker.choices.len()).unwrap_or(u16::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kind::QuestionKind;
+    use crate::quiz::score_answer;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn picker_moves_with_arrows_and_wraps() {
+        let mut picker = Picker::new(&["x", "y", "z"], false);
+
+        assert_eq!(picker.handle(press(KeyCode::Up)), KeyResult::Pending);
+        assert_eq!(picker.cursor, 2);
+        picker.handle(press(KeyCode::Down));
+        picker.handle(press(KeyCode::Down));
+
+        assert_eq!(
+            picker.handle(press(KeyCode::Enter)),
+            KeyResult::Done("y".to_string())
+        );
+    }
+
+    #[test]
+    fn picker_jumps_to_typed_label() {
+        let mut picker = Picker::new(&["x", "y", "z"], false);
+
+        picker.handle(press(KeyCode::Char('C')));
+
+        assert_eq!(picker.cursor, 2);
+        assert_eq!(picker.lines(), ["  a) x", "  b) y", "> c) z"]);
+
+        picker.handle(press(KeyCode::Char('2')));
+        assert_eq!(picker.cursor, 1);
+        picker.handle(press(KeyCode::Char('4')));
+        assert_eq!(picker.cursor, 1);
+    }
+
+    #[test]
+    fn multi_picker_answers_with_every_picked_choice() {
+        let mut picker = Picker::new(&["x", "y", "z"], true);
+
+        picker.handle(press(KeyCode::Char(' ')));
+        picker.handle(press(KeyCode::Char('c')));
+        picker.handle(press(KeyCode::Down));
+
+        assert_eq!(picker.lines(), ["> [x] a) x", "  [ ] b) y", "  [x] c) z"]);
+        assert_eq!(
+            picker.handle(press(KeyCode::Enter)),
+            KeyResult::Done("x, z".to_string())
+        );
+    }
+
+    #[test]
+    fn picked_letter_choice_is_scored_by_its_text() {
+        let choices = ["b", "a", "c"];
+        let kind = QuestionKind::MultipleChoice {
+            correct: "a".to_string(),
+            incorrects: vec!["b".to_string(), "c".to_string()],
+        };
+        let mut picker = Picker::new(&choices, false);
+
+        let KeyResult::Done(answer) = picker.handle(press(KeyCode::Enter)) else {
+            panic!("no answer");
+        };
+
+        assert_eq!(answer, "b");
+        assert_eq!(score_answer(&kind, &choices, "b", &answer), 0.0);
+        picker.handle(press(KeyCode::Down));
+        let KeyResult::Done(answer) = picker.handle(press(KeyCode::Enter)) else {
+            panic!("no answer");
+        };
+        assert_eq!(score_answer(&kind, &choices, "b", &answer), 1.0);
+    }
+
+    #[test]
+    fn line_editor_edits_until_enter_or_quit() {
+        let mut editor = LineEditor::default();
+
+        for c in "parsi".chars() {
+            editor.handle(press(KeyCode::Char(c)));
+        }
+        editor.handle(press(KeyCode::Backspace));
+        editor.handle(press(KeyCode::Backspace));
+        editor.handle(press(KeyCode::Char('i')));
+        editor.handle(press(KeyCode::Char('s')));
+
+        assert_eq!(
+            editor.handle(press(KeyCode::Enter)),
+            KeyResult::Done("paris".to_string())
+        );
+        assert_eq!(
+            editor.handle(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            KeyResult::Quit
+        );
+    }
+}